@@ -0,0 +1,242 @@
+//! The pieces shared between the per-year solution crates and the `aoc` runner: the `Day` trait
+//! each puzzle implements, and the registry of days for each year.
+use qu::ick_use::*;
+use std::{
+    fmt,
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+
+/// A single day's puzzle.
+///
+/// Implement this for a unit struct called `Solution` in the day's module, then declare the module
+/// and add it to the `days!` list in the year's `lib.rs`.
+pub trait Day {
+    /// The parsed input, shared between both parts. It may borrow from the input text.
+    type Input<'a>;
+    type Answer1: Answer;
+    type Answer2: Answer;
+
+    /// Example inputs from the puzzle text, with their expected answers.
+    const EXAMPLES: &'static [Example] = &[];
+
+    fn parse(input: &str) -> Result<Self::Input<'_>>;
+    fn part1(input: &Self::Input<'_>) -> Result<Self::Answer1>;
+    fn part2(input: &Self::Input<'_>) -> Result<Self::Answer2>;
+
+    /// A report on the input beyond the answers (e.g. statistics, or what other readings of it
+    /// would give), for the `analyse` command. Days without one return `None`.
+    fn analyse(_input: &Self::Input<'_>) -> Option<Result<String>> {
+        None
+    }
+}
+
+/// The answer to one part of a puzzle.
+///
+/// `Display` gives the answer as it would be typed into the website. The other methods describe it
+/// for machine-readable output.
+pub trait Answer: fmt::Display + Send {
+    /// Whether the answer is a number, rather than text.
+    fn is_number(&self) -> bool {
+        false
+    }
+
+    /// Answers that are drawn rather than written (e.g. letters spelled out on a screen) return
+    /// the picture here.
+    fn rendered(&self) -> Option<Rendered> {
+        None
+    }
+}
+
+macro_rules! number_answers {
+    ($($ty:ty),*) => {
+        $(impl Answer for $ty {
+            fn is_number(&self) -> bool {
+                true
+            }
+        })*
+    };
+}
+
+number_answers!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+impl Answer for String {}
+impl Answer for &str {}
+
+/// A picture made of characters, one string per row.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rendered {
+    pub rows: Vec<String>,
+}
+
+/// An example input from the puzzle text.
+#[derive(Debug, Copy, Clone)]
+pub struct Example {
+    pub input: &'static str,
+    /// The expected answer to part 1, as it would be displayed.
+    pub part1: Option<&'static str>,
+    /// The expected answer to part 2, as it would be displayed.
+    pub part2: Option<&'static str>,
+}
+
+impl Example {
+    pub fn expected(&self, part: Part) -> Option<&'static str> {
+        match part {
+            Part::One => self.part1,
+            Part::Two => self.part2,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl Part {
+    pub fn from_number(part: u8) -> Result<Self> {
+        Ok(match part {
+            1 => Part::One,
+            2 => Part::Two,
+            _ => bail!("must specify part 1 or part 2"),
+        })
+    }
+
+    pub fn number(self) -> u8 {
+        match self {
+            Part::One => 1,
+            Part::Two => 2,
+        }
+    }
+}
+
+impl fmt::Display for Part {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Part::One => f.pad("1"),
+            Part::Two => f.pad("2"),
+        }
+    }
+}
+
+/// Object-safe version of `Day`, so that days with different input and answer types can live
+/// together in the registry.
+pub trait DynDay: Sync {
+    /// Parse `input` and solve `part` of the puzzle with it.
+    fn run(&self, input: &str, part: Part) -> Result<Run>;
+    /// Parse `input` without solving anything, to check whether it's accepted.
+    fn check_input(&self, input: &str) -> Result;
+    /// Parse `input` and report on it, or `None` if the day has no analysis.
+    fn analyse(&self, input: &str) -> Result<Option<String>>;
+    fn examples(&self) -> &'static [Example];
+}
+
+/// The answer to one part, along with how long it took to get it.
+pub struct Run {
+    pub answer: Box<dyn Answer>,
+    pub parse_time: Duration,
+    pub solve_time: Duration,
+}
+
+impl<D> DynDay for D
+where
+    D: Day + Sync,
+    D::Answer1: 'static,
+    D::Answer2: 'static,
+{
+    fn run(&self, input: &str, part: Part) -> Result<Run> {
+        let start = Instant::now();
+        let input = D::parse(input)?;
+        let parse_time = start.elapsed();
+        let start = Instant::now();
+        let answer: Box<dyn Answer> = match part {
+            Part::One => Box::new(D::part1(&input)?),
+            Part::Two => Box::new(D::part2(&input)?),
+        };
+        let solve_time = start.elapsed();
+        Ok(Run {
+            answer,
+            parse_time,
+            solve_time,
+        })
+    }
+
+    fn check_input(&self, input: &str) -> Result {
+        D::parse(input)?;
+        Ok(())
+    }
+
+    fn analyse(&self, input: &str) -> Result<Option<String>> {
+        D::analyse(&D::parse(input)?).transpose()
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        D::EXAMPLES
+    }
+}
+
+/// An entry in the day registry.
+pub struct Entry {
+    pub day: u8,
+    pub solution: &'static dyn DynDay,
+}
+
+impl Entry {
+    /// Get the `n`th (1-based) example input.
+    pub fn example(&self, n: usize) -> Result<&'static Example> {
+        n.checked_sub(1)
+            .and_then(|idx| self.solution.examples().get(idx))
+            .with_context(|| format!("day {} has no example {}", self.day, n))
+    }
+}
+
+/// All the solutions for one year.
+pub struct Year {
+    pub year: u16,
+    pub days: &'static [Entry],
+    /// The root directory of the year's crate.
+    pub dir: &'static str,
+}
+
+impl Year {
+    /// Find the solution for `day`.
+    pub fn find(&self, day: u8) -> Result<&'static Entry> {
+        self.days
+            .iter()
+            .find(|entry| entry.day == day)
+            .with_context(|| {
+                format!(
+                    "day {} of {} not yet implemented/out of bounds",
+                    day, self.year
+                )
+            })
+    }
+
+    /// Directory containing the puzzle inputs, one file per day.
+    pub fn input_dir(&self) -> PathBuf {
+        Path::new(self.dir).join("input")
+    }
+
+    /// Directory containing the day modules.
+    pub fn src_dir(&self) -> PathBuf {
+        Path::new(self.dir).join("src")
+    }
+}
+
+/// Build the year's registry (`YEAR`) from its day modules, which are declared separately with
+/// plain `mod` items so that `cargo fmt` finds them.
+///
+/// Each module must contain a `Solution` type that implements `Day`.
+#[macro_export]
+macro_rules! days {
+    ($year:literal; $($day:literal => $module:ident),* $(,)?) => {
+        pub const YEAR: $crate::Year = $crate::Year {
+            year: $year,
+            days: &[
+                $($crate::Entry { day: $day, solution: &$module::Solution },)*
+            ],
+            dir: env!("CARGO_MANIFEST_DIR"),
+        };
+    };
+}
@@ -0,0 +1,127 @@
+use crate::{Point, Vec2};
+use std::ops::{Add, AddAssign};
+
+/// The four orthogonal directions, in clockwise order starting from up.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Dir4 {
+    Up,
+    Right,
+    Down,
+    Left,
+}
+
+impl Dir4 {
+    pub const ALL: [Dir4; 4] = [Dir4::Up, Dir4::Right, Dir4::Down, Dir4::Left];
+
+    /// The unit step in this direction.
+    pub fn offset(self) -> Vec2 {
+        match self {
+            Dir4::Up => Point::new(0, -1),
+            Dir4::Right => Point::new(1, 0),
+            Dir4::Down => Point::new(0, 1),
+            Dir4::Left => Point::new(-1, 0),
+        }
+    }
+
+    pub fn turn_right(self) -> Self {
+        Self::ALL[(self as usize + 1) % 4]
+    }
+
+    pub fn turn_left(self) -> Self {
+        Self::ALL[(self as usize + 3) % 4]
+    }
+
+    pub fn opposite(self) -> Self {
+        Self::ALL[(self as usize + 2) % 4]
+    }
+}
+
+/// The four orthogonal and four diagonal directions, in clockwise order starting from up.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Dir8 {
+    Up,
+    UpRight,
+    Right,
+    DownRight,
+    Down,
+    DownLeft,
+    Left,
+    UpLeft,
+}
+
+impl Dir8 {
+    pub const ALL: [Dir8; 8] = [
+        Dir8::Up,
+        Dir8::UpRight,
+        Dir8::Right,
+        Dir8::DownRight,
+        Dir8::Down,
+        Dir8::DownLeft,
+        Dir8::Left,
+        Dir8::UpLeft,
+    ];
+
+    /// The unit step in this direction.
+    pub fn offset(self) -> Vec2 {
+        match self {
+            Dir8::Up => Point::new(0, -1),
+            Dir8::UpRight => Point::new(1, -1),
+            Dir8::Right => Point::new(1, 0),
+            Dir8::DownRight => Point::new(1, 1),
+            Dir8::Down => Point::new(0, 1),
+            Dir8::DownLeft => Point::new(-1, 1),
+            Dir8::Left => Point::new(-1, 0),
+            Dir8::UpLeft => Point::new(-1, -1),
+        }
+    }
+}
+
+impl From<Dir4> for Dir8 {
+    fn from(dir: Dir4) -> Self {
+        match dir {
+            Dir4::Up => Dir8::Up,
+            Dir4::Right => Dir8::Right,
+            Dir4::Down => Dir8::Down,
+            Dir4::Left => Dir8::Left,
+        }
+    }
+}
+
+impl Add<Dir4> for Point {
+    type Output = Point;
+
+    fn add(self, dir: Dir4) -> Point {
+        self + dir.offset()
+    }
+}
+
+impl AddAssign<Dir4> for Point {
+    fn add_assign(&mut self, dir: Dir4) {
+        *self += dir.offset();
+    }
+}
+
+impl Add<Dir8> for Point {
+    type Output = Point;
+
+    fn add(self, dir: Dir8) -> Point {
+        self + dir.offset()
+    }
+}
+
+impl AddAssign<Dir8> for Point {
+    fn add_assign(&mut self, dir: Dir8) {
+        *self += dir.offset();
+    }
+}
+
+#[test]
+fn test_turns() {
+    assert_eq!(Dir4::Left.turn_right(), Dir4::Up);
+    assert_eq!(Dir4::Up.turn_left(), Dir4::Left);
+    assert_eq!(Dir4::Down.opposite(), Dir4::Up);
+    for dir in Dir4::ALL {
+        assert_eq!(dir.offset(), Dir8::from(dir).offset());
+        assert_eq!(dir.offset() + dir.opposite().offset(), Point::ZERO);
+    }
+}
@@ -0,0 +1,154 @@
+use crate::{Point, Vec2};
+use qu::ick_use::*;
+use std::{
+    fmt,
+    ops::{Index, IndexMut},
+};
+
+/// A rectangular grid of cells, stored row-major.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grid<T> {
+    cells: Vec<T>,
+    width: usize,
+    height: usize,
+}
+
+impl<T> Grid<T> {
+    /// A `width` by `height` grid with every cell set to `fill`.
+    pub fn new(width: usize, height: usize, fill: T) -> Self
+    where
+        T: Clone,
+    {
+        Grid {
+            cells: vec![fill; width * height],
+            width,
+            height,
+        }
+    }
+
+    /// Parse a grid with one cell per character and one row per line. Every line must be the same
+    /// length.
+    pub fn parse(input: &str, mut cell: impl FnMut(char) -> Result<T>) -> Result<Self> {
+        let mut cells = vec![];
+        let mut width = None;
+        let mut height = 0;
+        for (idx, line) in input.lines().enumerate() {
+            let start = cells.len();
+            for ch in line.chars() {
+                cells.push(cell(ch)?);
+            }
+            let len = cells.len() - start;
+            match width {
+                None => width = Some(len),
+                Some(width) => ensure!(
+                    len == width,
+                    "line {} has {} cells but the first line has {}",
+                    idx + 1,
+                    len,
+                    width
+                ),
+            }
+            height += 1;
+        }
+        let width = width.context("no input data")?;
+        ensure!(width > 0, "grid rows should not be empty");
+        Ok(Grid {
+            cells,
+            width,
+            height,
+        })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn contains(&self, p: Point) -> bool {
+        (0..self.width as isize).contains(&p.x) && (0..self.height as isize).contains(&p.y)
+    }
+
+    pub fn get(&self, p: Point) -> Option<&T> {
+        let idx = self.index_of(p)?;
+        Some(&self.cells[idx])
+    }
+
+    pub fn get_mut(&mut self, p: Point) -> Option<&mut T> {
+        let idx = self.index_of(p)?;
+        Some(&mut self.cells[idx])
+    }
+
+    /// Every point in the grid, row by row.
+    pub fn points(&self) -> impl Iterator<Item = Point> {
+        let (width, height) = (self.width as isize, self.height as isize);
+        (0..height).flat_map(move |y| (0..width).map(move |x| Point::new(x, y)))
+    }
+
+    /// Every cell in the grid with its position, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = (Point, &T)> {
+        self.points().zip(&self.cells)
+    }
+
+    /// The points reached by repeatedly stepping by `step` from `from` (not including `from`
+    /// itself), until we leave the grid.
+    pub fn ray(&self, from: Point, step: Vec2) -> impl Iterator<Item = Point> + '_ {
+        std::iter::successors(Some(from + step), move |&p| Some(p + step))
+            .take_while(|&p| self.contains(p))
+    }
+
+    fn index_of(&self, p: Point) -> Option<usize> {
+        if self.contains(p) {
+            Some(p.y as usize * self.width + p.x as usize)
+        } else {
+            None
+        }
+    }
+}
+
+impl<T> Index<Point> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, p: Point) -> &T {
+        match self.get(p) {
+            Some(cell) => cell,
+            None => panic!("{} is outside the {}x{} grid", p, self.width, self.height),
+        }
+    }
+}
+
+impl<T> IndexMut<Point> for Grid<T> {
+    fn index_mut(&mut self, p: Point) -> &mut T {
+        let (width, height) = (self.width, self.height);
+        match self.get_mut(p) {
+            Some(cell) => cell,
+            None => panic!("{} is outside the {}x{} grid", p, width, height),
+        }
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for row in self.cells.chunks(self.width) {
+            for cell in row {
+                write!(f, "{}", cell)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[test]
+fn test_parse() {
+    let grid = Grid::parse("ab\ncd\nef\n", Ok).unwrap();
+    assert_eq!((grid.width(), grid.height()), (2, 3));
+    assert_eq!(grid[Point::new(1, 2)], 'f');
+    assert_eq!(grid.get(Point::new(2, 0)), None);
+    assert_eq!(grid.to_string(), "ab\ncd\nef\n");
+    assert!(Grid::parse("ab\nc\n", Ok).is_err());
+    let ray: Vec<_> = grid.ray(Point::new(0, 0), Point::new(1, 1)).collect();
+    assert_eq!(ray, [Point::new(1, 1)]);
+}
@@ -0,0 +1,12 @@
+//! Helpers that come up again and again across puzzles: 2D grids, points and directions, nom
+//! parsers for the bits of syntax every input uses, and fixed-size sliding windows.
+mod dir;
+mod grid;
+pub mod parse;
+mod point;
+mod windows;
+
+pub use dir::{Dir4, Dir8};
+pub use grid::Grid;
+pub use point::{Point, Vec2};
+pub use windows::{ConstWindows, Windows};
@@ -0,0 +1,290 @@
+//! nom parsers shared between days, and the error type they all report through.
+//!
+//! Parsers use [`IResult`], which carries an [`Error`] recording where parsing failed and what was
+//! expected there. Run them with [`parse_all`], [`parse_lines`] or [`parse_line`] to turn that into
+//! a [`ParseError`], which knows the line and column and renders a caret diagnostic.
+use nom::{
+    character::complete::digit1,
+    combinator::{opt, recognize},
+    error::{ContextError, ErrorKind, FromExternalError, ParseError as NomParseError},
+    multi::separated_list1,
+    sequence::pair,
+    Parser,
+};
+use std::{any::type_name, fmt, str::FromStr};
+
+pub type IResult<'a, O> = nom::IResult<&'a str, O, Error<'a>>;
+
+/// Something a parser was expecting to find.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Expected {
+    /// Some literal text.
+    Token(&'static str),
+    Char(char),
+    /// A description of what should be there, e.g. "a number".
+    Named(&'static str),
+    /// Something a nom parser was looking for, that we have no better description of.
+    Kind(ErrorKind),
+    /// Input that could be read but not converted, with the reason why.
+    Valid(String),
+}
+
+impl fmt::Display for Expected {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Expected::Token(token) => write!(f, "`{}`", token.escape_debug()),
+            Expected::Char(ch) => write!(f, "`{}`", ch.escape_debug()),
+            Expected::Named(name) => f.write_str(name),
+            Expected::Kind(ErrorKind::Eof) => f.write_str("end of input"),
+            Expected::Kind(kind) => write!(f, "{}", kind.description().to_lowercase()),
+            Expected::Valid(reason) => write!(f, "valid input ({})", reason),
+        }
+    }
+}
+
+/// The error type for our nom parsers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Error<'a> {
+    /// The remaining input where parsing failed.
+    pub input: &'a str,
+    /// All the alternatives that would have been accepted here.
+    pub expected: Vec<Expected>,
+}
+
+impl<'a> Error<'a> {
+    pub fn new(input: &'a str, expected: Expected) -> Self {
+        Error {
+            input,
+            expected: vec![expected],
+        }
+    }
+}
+
+impl<'a> NomParseError<&'a str> for Error<'a> {
+    fn from_error_kind(input: &'a str, kind: ErrorKind) -> Self {
+        Error::new(input, Expected::Kind(kind))
+    }
+
+    fn append(_: &'a str, _: ErrorKind, other: Self) -> Self {
+        // the innermost error says the most about what went wrong
+        other
+    }
+
+    fn from_char(input: &'a str, ch: char) -> Self {
+        Error::new(input, Expected::Char(ch))
+    }
+
+    fn or(mut self, other: Self) -> Self {
+        // keep whichever branch got furthest, or all of them if they failed in the same place
+        match self.input.len().cmp(&other.input.len()) {
+            std::cmp::Ordering::Less => self,
+            std::cmp::Ordering::Greater => other,
+            std::cmp::Ordering::Equal => {
+                for expected in other.expected {
+                    if !self.expected.contains(&expected) {
+                        self.expected.push(expected);
+                    }
+                }
+                self
+            }
+        }
+    }
+}
+
+impl<'a> ContextError<&'a str> for Error<'a> {
+    /// If the parser failed before consuming anything, describe what it expected with `ctx`.
+    fn add_context(input: &'a str, ctx: &'static str, other: Self) -> Self {
+        if other.input.len() == input.len() {
+            Error::new(input, Expected::Named(ctx))
+        } else {
+            other
+        }
+    }
+}
+
+impl<'a, E: fmt::Display> FromExternalError<&'a str, E> for Error<'a> {
+    fn from_external_error(input: &'a str, _: ErrorKind, e: E) -> Self {
+        Error::new(input, Expected::Valid(e.to_string()))
+    }
+}
+
+/// A parse failure, located in the original input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// 1-based line number.
+    pub line: usize,
+    /// 1-based column, counted in characters.
+    pub column: usize,
+    /// What we expected to find, e.g. "`addx ` or `noop`".
+    pub expected: String,
+    /// The text of the offending line.
+    pub source_line: String,
+}
+
+impl ParseError {
+    /// Locate `error` in `source`, which it must have come from.
+    pub fn new(source: &str, error: Error<'_>) -> Self {
+        let offset = (error.input.as_ptr() as usize).wrapping_sub(source.as_ptr() as usize);
+        assert!(offset <= source.len(), "error is not from this input");
+        let line_start = source[..offset].rfind('\n').map_or(0, |idx| idx + 1);
+        let line_end = source[offset..]
+            .find('\n')
+            .map_or(source.len(), |idx| offset + idx);
+        ParseError {
+            line: source[..offset].matches('\n').count() + 1,
+            column: source[line_start..offset].chars().count() + 1,
+            expected: join_expected(&error.expected),
+            source_line: source[line_start..line_end].trim_end_matches('\r').into(),
+        }
+    }
+}
+
+/// Join a list of alternatives into "a", "a or b", "a, b or c".
+fn join_expected(expected: &[Expected]) -> String {
+    let mut out = String::new();
+    for (idx, item) in expected.iter().enumerate() {
+        if idx > 0 {
+            out.push_str(if idx == expected.len() - 1 {
+                " or "
+            } else {
+                ", "
+            });
+        }
+        out.push_str(&item.to_string());
+    }
+    out
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let gutter = self.line.to_string().len();
+        writeln!(
+            f,
+            "expected {} at line {}, column {}",
+            self.expected, self.line, self.column
+        )?;
+        writeln!(f, "{:gutter$} |", "")?;
+        writeln!(f, "{} | {}", self.line, self.source_line)?;
+        write!(f, "{:gutter$} | {:>col$}", "", "^", col = self.column)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Run `parser` over the whole of `input`.
+pub fn parse_all<'a, O>(
+    input: &'a str,
+    parser: impl Parser<&'a str, O, Error<'a>>,
+) -> Result<O, ParseError> {
+    parse_line(input, input, parser)
+}
+
+/// Run `parser` over each line of `input`.
+pub fn parse_lines<'a, O>(
+    input: &'a str,
+    mut parser: impl Parser<&'a str, O, Error<'a>>,
+) -> Result<Vec<O>, ParseError> {
+    input
+        .lines()
+        .map(|line| parse_line(input, line, |i| parser.parse(i)))
+        .collect()
+}
+
+/// Run `parser` over `line`, which must be borrowed from `input`, so that errors point to the
+/// right place in `input`. `parser` must consume the whole line.
+pub fn parse_line<'a, O>(
+    input: &'a str,
+    line: &'a str,
+    mut parser: impl Parser<&'a str, O, Error<'a>>,
+) -> Result<O, ParseError> {
+    let error = match parser.parse(line) {
+        Ok(("", out)) => return Ok(out),
+        Ok((rest, _)) => Error::new(rest, Expected::Kind(ErrorKind::Eof)),
+        Err(nom::Err::Error(e) | nom::Err::Failure(e)) => e,
+        Err(nom::Err::Incomplete(_)) => Error::new("", Expected::Kind(ErrorKind::Eof)),
+    };
+    let error = match error.input {
+        // point at the end of the line, rather than the unrelated empty string
+        "" => Error {
+            input: &line[line.len()..],
+            ..error
+        },
+        _ => error,
+    };
+    Err(ParseError::new(input, error))
+}
+
+/// Match some literal text.
+pub fn tag<'a>(token: &'static str) -> impl Fn(&'a str) -> IResult<'a, &'a str> {
+    move |i: &'a str| match i.strip_prefix(token) {
+        Some(rest) => Ok((rest, &i[..token.len()])),
+        None => Err(nom::Err::Error(Error::new(i, Expected::Token(token)))),
+    }
+}
+
+/// An unsigned decimal number.
+pub fn number<'a, T: FromStr>(i: &'a str) -> IResult<'a, T> {
+    let (rest, digits) = digit1::<_, Error>(i).map_err(|_| expected_number(i))?;
+    Ok((rest, convert(i, digits)?))
+}
+
+/// A decimal number with an optional leading `-`.
+pub fn signed<'a, T: FromStr>(i: &'a str) -> IResult<'a, T> {
+    let (rest, digits) =
+        recognize(pair(opt(tag("-")), digit1::<_, Error>))(i).map_err(|_| expected_number(i))?;
+    Ok((rest, convert(i, digits)?))
+}
+
+fn expected_number(i: &str) -> nom::Err<Error<'_>> {
+    nom::Err::Error(Error::new(i, Expected::Named("a number")))
+}
+
+fn convert<'a, T: FromStr>(i: &'a str, digits: &str) -> Result<T, nom::Err<Error<'a>>> {
+    digits.parse().map_err(|_| {
+        let reason = format!("{} does not fit in {}", digits, type_name::<T>());
+        nom::Err::Failure(Error::new(i, Expected::Valid(reason)))
+    })
+}
+
+/// One or more `item`s separated by `sep`, e.g. `list(", ", number)` for `1, 2, 3`.
+pub fn list<'a, O>(
+    sep: &'static str,
+    item: impl Parser<&'a str, O, Error<'a>>,
+) -> impl FnMut(&'a str) -> IResult<'a, Vec<O>> {
+    separated_list1(tag(sep), item)
+}
+
+#[test]
+fn test_numbers() {
+    assert_eq!(number::<u32>("123 rest"), Ok((" rest", 123)));
+    assert!(number::<u32>("-1").is_err());
+    assert!(number::<u8>("256").is_err());
+    assert_eq!(signed::<i32>("-15"), Ok(("", -15)));
+    assert_eq!(
+        list(", ", number::<u32>)("79, 98\n"),
+        Ok(("\n", vec![79, 98]))
+    );
+}
+
+#[test]
+fn test_diagnostic() {
+    use nom::{branch::alt, sequence::preceded};
+
+    let input = "noop\naddx 3\naddy 4\n";
+    let inst = |i| alt((tag("noop"), preceded(tag("addx "), tag("3"))))(i);
+    let err = parse_lines(input, inst).unwrap_err();
+    assert_eq!((err.line, err.column), (3, 1));
+    assert_eq!(
+        err.to_string(),
+        "expected `noop` or `addx ` at line 3, column 1
+  |
+3 | addy 4
+  | ^"
+    );
+
+    let err = parse_all("addx 300", preceded(tag("addx "), number::<u8>)).unwrap_err();
+    assert_eq!(err.column, 6);
+    assert_eq!(err.expected, "valid input (300 does not fit in u8)");
+    let err = parse_all("addx 3 ", preceded(tag("addx "), number::<u8>)).unwrap_err();
+    assert_eq!((err.column, err.expected.as_str()), (7, "end of input"));
+}
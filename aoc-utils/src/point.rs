@@ -0,0 +1,108 @@
+use std::{
+    fmt,
+    ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign},
+};
+
+/// A position on a 2D grid, with `x` increasing to the right and `y` increasing downwards.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Point {
+    pub x: isize,
+    pub y: isize,
+}
+
+/// The difference between two points.
+pub type Vec2 = Point;
+
+impl Point {
+    pub const ZERO: Point = Point { x: 0, y: 0 };
+
+    pub const fn new(x: isize, y: isize) -> Self {
+        Point { x, y }
+    }
+
+    /// The sign of each coordinate, so every component is -1, 0 or 1.
+    pub fn signum(self) -> Self {
+        Point::new(self.x.signum(), self.y.signum())
+    }
+
+    pub fn abs(self) -> Self {
+        Point::new(self.x.abs(), self.y.abs())
+    }
+
+    /// The taxicab distance from the origin.
+    pub fn manhattan(self) -> usize {
+        self.x.unsigned_abs() + self.y.unsigned_abs()
+    }
+
+    /// The number of king moves it takes to get here from the origin.
+    pub fn chebyshev(self) -> usize {
+        self.x.unsigned_abs().max(self.y.unsigned_abs())
+    }
+}
+
+impl From<(isize, isize)> for Point {
+    fn from((x, y): (isize, isize)) -> Self {
+        Point::new(x, y)
+    }
+}
+
+impl fmt::Display for Point {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "({}, {})", self.x, self.y)
+    }
+}
+
+impl Add for Point {
+    type Output = Point;
+
+    fn add(self, rhs: Point) -> Point {
+        Point::new(self.x + rhs.x, self.y + rhs.y)
+    }
+}
+
+impl AddAssign for Point {
+    fn add_assign(&mut self, rhs: Point) {
+        *self = *self + rhs;
+    }
+}
+
+impl Sub for Point {
+    type Output = Point;
+
+    fn sub(self, rhs: Point) -> Point {
+        Point::new(self.x - rhs.x, self.y - rhs.y)
+    }
+}
+
+impl SubAssign for Point {
+    fn sub_assign(&mut self, rhs: Point) {
+        *self = *self - rhs;
+    }
+}
+
+impl Neg for Point {
+    type Output = Point;
+
+    fn neg(self) -> Point {
+        Point::new(-self.x, -self.y)
+    }
+}
+
+impl Mul<isize> for Point {
+    type Output = Point;
+
+    fn mul(self, rhs: isize) -> Point {
+        Point::new(self.x * rhs, self.y * rhs)
+    }
+}
+
+#[test]
+fn test_arithmetic() {
+    let p = Point::new(3, -2);
+    assert_eq!(p + Point::new(1, 1), Point::new(4, -1));
+    assert_eq!(p - p, Point::ZERO);
+    assert_eq!(-p * 2, Point::new(-6, 4));
+    assert_eq!(p.signum(), Point::new(1, -1));
+    assert_eq!(p.manhattan(), 5);
+    assert_eq!(p.chebyshev(), 3);
+}
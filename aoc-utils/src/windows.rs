@@ -0,0 +1,46 @@
+/// Overlapping fixed-size windows over a slice, like `slice::windows` but yielding arrays so the
+/// size is known at compile time.
+pub trait ConstWindows<T> {
+    /// An iterator over every window of length `N`, e.g. `b"abcd".const_windows::<3>()` yields
+    /// `b"abc"` then `b"bcd"`. Yields nothing if the slice is shorter than `N`.
+    fn const_windows<const N: usize>(&self) -> Windows<'_, T, N>;
+}
+
+impl<T> ConstWindows<T> for [T] {
+    fn const_windows<const N: usize>(&self) -> Windows<'_, T, N> {
+        assert!(N > 0, "window size must be non-zero");
+        Windows { slice: self }
+    }
+}
+
+/// Iterator returned by [`ConstWindows::const_windows`].
+#[derive(Debug, Clone)]
+pub struct Windows<'a, T, const N: usize> {
+    /// The slice starting at the next window.
+    slice: &'a [T],
+}
+
+impl<'a, T, const N: usize> Iterator for Windows<'a, T, N> {
+    type Item = &'a [T; N];
+
+    fn next(&mut self) -> Option<&'a [T; N]> {
+        let window = self.slice.first_chunk::<N>()?;
+        self.slice = &self.slice[1..];
+        Some(window)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = (self.slice.len() + 1).saturating_sub(N);
+        (len, Some(len))
+    }
+}
+
+impl<T, const N: usize> ExactSizeIterator for Windows<'_, T, N> {}
+
+#[test]
+fn test_const_windows() {
+    let windows: Vec<_> = b"abcd".const_windows::<3>().collect();
+    assert_eq!(windows, [b"abc", b"bcd"]);
+    assert_eq!(b"ab".const_windows::<3>().count(), 0);
+    assert_eq!(b"abcd".const_windows::<1>().len(), 4);
+}
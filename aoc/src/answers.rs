@@ -0,0 +1,76 @@
+use crate::{
+    input,
+    runner::{self, Outcome},
+    store::Store,
+};
+use aoc_core::{Entry, Part, Year};
+
+/// Default location of the expected answers file (shared between years).
+pub const ANSWERS_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../answers.toml");
+
+/// Known-correct answers, keyed by year, day then part.
+pub type Answers = Store<String>;
+
+/// Run every part in `days` of `year` and check the answers against `answers`.
+///
+/// If `record` is set, the answers we get are stored in `answers` rather than checked. Returns
+/// the number of answers that didn't match or parts that failed.
+pub fn verify(year: &Year, days: &[Entry], answers: &mut Answers, record: bool) -> usize {
+    let mut bad = 0;
+    for entry in days {
+        let input = input::load(year, entry.day, None);
+        for part in [Part::One, Part::Two] {
+            let outcome = match &input {
+                Ok(input) => runner::run_part(entry, input, part).0,
+                Err(e) => Outcome::error(e),
+            };
+            print!("{} day {:>2} part {}: ", year.year, entry.day, part);
+            let answer = match outcome {
+                Outcome::Solved(run) => run.answer.to_string(),
+                Outcome::Unimplemented => {
+                    println!("unimplemented");
+                    continue;
+                }
+                outcome => {
+                    println!("{}", outcome);
+                    bad += 1;
+                    continue;
+                }
+            };
+            if record {
+                println!("recorded");
+                answers.insert(year.year, entry.day, part, answer);
+                continue;
+            }
+            match answers.get(year.year, entry.day, part) {
+                Some(expected) if *expected == answer => println!("ok"),
+                Some(expected) => {
+                    println!("MISMATCH");
+                    print_diff(expected, &answer);
+                    bad += 1;
+                }
+                None => println!("no answer recorded"),
+            }
+        }
+    }
+    bad
+}
+
+/// Print a line-by-line diff between the expected and actual answers.
+fn print_diff(expected: &str, actual: &str) {
+    let expected = expected.lines().collect::<Vec<_>>();
+    let actual = actual.lines().collect::<Vec<_>>();
+    for idx in 0..expected.len().max(actual.len()) {
+        match (expected.get(idx), actual.get(idx)) {
+            (Some(e), Some(a)) if e == a => println!("      {}", e),
+            (e, a) => {
+                if let Some(e) = e {
+                    println!("    - {}", e);
+                }
+                if let Some(a) = a {
+                    println!("    + {}", a);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,134 @@
+use crate::store::Store;
+use aoc_core::{Entry, Part};
+use qu::ick_use::*;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// Default location of the saved benchmark results we compare against.
+pub const BASELINE_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../bench-baseline.toml");
+
+/// How much slower (as a fraction) the median can be than the baseline before we call it a
+/// regression.
+const REGRESSION_THRESHOLD: f64 = 0.1;
+
+/// Summary statistics for a set of timings.
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+pub struct Stats {
+    #[serde(with = "nanos")]
+    pub min: Duration,
+    #[serde(with = "nanos")]
+    pub median: Duration,
+    #[serde(with = "nanos")]
+    pub p95: Duration,
+}
+
+impl Stats {
+    /// Calculate stats for `samples`, which must not be empty.
+    fn new(mut samples: Vec<Duration>) -> Self {
+        assert!(!samples.is_empty());
+        samples.sort();
+        // nearest-rank percentile
+        let percentile = |p: usize| samples[(samples.len() * p).div_ceil(100).max(1) - 1];
+        Stats {
+            min: samples[0],
+            median: percentile(50),
+            p95: percentile(95),
+        }
+    }
+}
+
+/// Parse and solve stats for one part.
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+pub struct Bench {
+    pub parse: Stats,
+    pub solve: Stats,
+}
+
+/// Benchmark results, keyed by year, day then part.
+pub type Baseline = Store<Bench>;
+
+/// Run `part` of `entry` `iterations` times (after one warm-up run) and collect timings.
+pub fn bench(entry: &Entry, input: &str, part: Part, iterations: usize) -> Result<Bench> {
+    ensure!(iterations > 0, "must run at least 1 iteration");
+    entry.solution.run(input, part)?;
+    let mut parse = Vec::with_capacity(iterations);
+    let mut solve = Vec::with_capacity(iterations);
+    for _ in 0..iterations {
+        let run = entry.solution.run(input, part)?;
+        parse.push(run.parse_time);
+        solve.push(run.solve_time);
+    }
+    Ok(Bench {
+        parse: Stats::new(parse),
+        solve: Stats::new(solve),
+    })
+}
+
+/// Print `bench` alongside the `baseline` if there is one.
+///
+/// Returns `true` if either median has regressed compared to the baseline.
+pub fn report(year: u16, day: u8, part: Part, bench: &Bench, baseline: Option<&Bench>) -> bool {
+    println!("{} day {} part {}", year, day, part);
+    println!("  {:<6} {:>12} {:>12} {:>12}", "", "min", "median", "p95");
+    for (name, stats) in [("parse", &bench.parse), ("solve", &bench.solve)] {
+        println!(
+            "  {:<6} {:>12} {:>12} {:>12}",
+            name,
+            format!("{:.2?}", stats.min),
+            format!("{:.2?}", stats.median),
+            format!("{:.2?}", stats.p95)
+        );
+    }
+    let Some(baseline) = baseline else {
+        return false;
+    };
+    let mut regressed = false;
+    for (name, now, then) in [
+        ("parse", bench.parse.median, baseline.parse.median),
+        ("solve", bench.solve.median, baseline.solve.median),
+    ] {
+        let change = now.as_secs_f64() / then.as_secs_f64() - 1.;
+        let flag = if change > REGRESSION_THRESHOLD {
+            regressed = true;
+            "  REGRESSION"
+        } else {
+            ""
+        };
+        println!(
+            "  {} median vs baseline: {:.2?} -> {:.2?} ({:+.1}%){}",
+            name,
+            then,
+            now,
+            change * 100.,
+            flag
+        );
+    }
+    regressed
+}
+
+/// (De)serialize durations as a whole number of nanoseconds.
+mod nanos {
+    use serde::{Deserialize, Deserializer, Serializer};
+    use std::time::Duration;
+
+    pub fn serialize<S: Serializer>(d: &Duration, s: S) -> Result<S::Ok, S::Error> {
+        s.serialize_u64(d.as_nanos().try_into().unwrap_or(u64::MAX))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<Duration, D::Error> {
+        u64::deserialize(d).map(Duration::from_nanos)
+    }
+}
+
+#[test]
+fn test_stats() {
+    let samples = (1..=100).rev().map(Duration::from_millis).collect();
+    let stats = Stats::new(samples);
+    assert_eq!(stats.min, Duration::from_millis(1));
+    assert_eq!(stats.median, Duration::from_millis(50));
+    assert_eq!(stats.p95, Duration::from_millis(95));
+
+    let stats = Stats::new(vec![Duration::from_millis(3)]);
+    assert_eq!(stats.median, Duration::from_millis(3));
+    assert_eq!(stats.p95, Duration::from_millis(3));
+}
@@ -0,0 +1,242 @@
+//! Talking to the puzzle website.
+use crate::config::Config;
+use aoc_core::Part;
+use qu::ick_use::*;
+use std::fmt;
+use ureq::Agent;
+
+const USER_AGENT: &str = concat!("aoc-runner/", env!("CARGO_PKG_VERSION"));
+
+/// The website's response to an answer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Verdict {
+    Correct,
+    Wrong,
+    TooHigh,
+    TooLow,
+    /// We submitted too recently. `wait` is how long the site says to wait, if it said.
+    RateLimited {
+        wait: Option<String>,
+    },
+    /// The part has already been solved (or isn't unlocked yet).
+    WrongLevel,
+}
+
+impl Verdict {
+    /// Is this a wrong answer (as opposed to no judgement at all)?
+    pub fn is_wrong(&self) -> bool {
+        matches!(self, Verdict::Wrong | Verdict::TooHigh | Verdict::TooLow)
+    }
+
+    /// Work out the verdict from the page the site returns after submitting.
+    pub fn from_page(page: &str) -> Result<Self> {
+        Ok(if page.contains("That's the right answer") {
+            Verdict::Correct
+        } else if page.contains("That's not the right answer") {
+            if page.contains("your answer is too high") {
+                Verdict::TooHigh
+            } else if page.contains("your answer is too low") {
+                Verdict::TooLow
+            } else {
+                Verdict::Wrong
+            }
+        } else if page.contains("You gave an answer too recently") {
+            let wait = page
+                .split_once("You have ")
+                .and_then(|(_, rest)| rest.split_once(" left to wait"))
+                .map(|(wait, _)| wait.to_owned());
+            Verdict::RateLimited { wait }
+        } else if page.contains("You don't seem to be solving the right level") {
+            Verdict::WrongLevel
+        } else {
+            bail!("couldn't understand the response to our answer")
+        })
+    }
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Verdict::Correct => f.write_str("correct"),
+            Verdict::Wrong => f.write_str("wrong"),
+            Verdict::TooHigh => f.write_str("too high"),
+            Verdict::TooLow => f.write_str("too low"),
+            Verdict::RateLimited { wait: Some(wait) } => {
+                write!(f, "rate limited (wait {})", wait)
+            }
+            Verdict::RateLimited { wait: None } => f.write_str("rate limited"),
+            Verdict::WrongLevel => f.write_str("already solved or not unlocked"),
+        }
+    }
+}
+
+/// A logged-in connection to the website.
+pub struct Client {
+    agent: Agent,
+    base_url: String,
+    session: String,
+}
+
+impl Client {
+    /// Connect using the server and session token from `config`.
+    pub fn new(config: &Config) -> Result<Self> {
+        let agent = Agent::config_builder()
+            .http_status_as_error(false)
+            .user_agent(USER_AGENT)
+            .build()
+            .into();
+        Ok(Client {
+            agent,
+            base_url: config.base_url.trim_end_matches('/').to_owned(),
+            session: config.session()?.to_owned(),
+        })
+    }
+
+    /// Download the puzzle input for `day`.
+    pub fn input(&self, year: u16, day: u8) -> Result<String> {
+        let url = format!("{}/{}/day/{}/input", self.base_url, year, day);
+        let mut response = self
+            .agent
+            .get(&url)
+            .header("Cookie", &format!("session={}", self.session))
+            .call()
+            .with_context(|| format!("downloading {}", url))?;
+        let status = response.status();
+        match status.as_u16() {
+            200 => (),
+            400 | 500 => bail!(
+                "server returned {} for {}: is the session token valid?",
+                status,
+                url
+            ),
+            404 => bail!(
+                "server returned {} for {}: is the puzzle unlocked yet?",
+                status,
+                url
+            ),
+            _ => bail!("server returned {} for {}", status, url),
+        }
+        response
+            .body_mut()
+            .with_config()
+            .limit(10 * 1024 * 1024)
+            .read_to_string()
+            .context("reading input")
+    }
+
+    /// Submit `answer` for `part` of `day`.
+    pub fn submit(&self, year: u16, day: u8, part: Part, answer: &str) -> Result<Verdict> {
+        let url = format!("{}/{}/day/{}/answer", self.base_url, year, day);
+        let level = part.number().to_string();
+        let mut response = self
+            .agent
+            .post(&url)
+            .header("Cookie", &format!("session={}", self.session))
+            .send_form([("level", level.as_str()), ("answer", answer)])
+            .with_context(|| format!("posting answer to {}", url))?;
+        let status = response.status();
+        ensure!(
+            status.is_success(),
+            "server returned {} for {}",
+            status,
+            url
+        );
+        let page = response
+            .body_mut()
+            .read_to_string()
+            .context("reading response")?;
+        Verdict::from_page(&page)
+    }
+}
+
+/// A stand-in for the website that answers a single request, for testing.
+#[cfg(test)]
+pub mod mock {
+    use std::{
+        io::{BufRead, BufReader, Read, Write},
+        net::TcpListener,
+        thread::{self, JoinHandle},
+    };
+
+    /// Start a server that responds to one request with `status` and `body`. Returns its base URL
+    /// and a handle that yields the request it received.
+    pub fn serve_once(status: u16, body: &'static str) -> (String, JoinHandle<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let handle = thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream);
+            let mut request = String::new();
+            let mut content_length = 0;
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if let Some((name, value)) = line.split_once(':') {
+                    if name.eq_ignore_ascii_case("content-length") {
+                        content_length = value.trim().parse().unwrap();
+                    }
+                }
+                request.push_str(&line);
+                if line == "\r\n" || line.is_empty() {
+                    break;
+                }
+            }
+            let mut body_buf = vec![0; content_length];
+            reader.read_exact(&mut body_buf).unwrap();
+            request.push_str(&String::from_utf8(body_buf).unwrap());
+            write!(
+                reader.get_mut(),
+                "HTTP/1.1 {} OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                status,
+                body.len(),
+                body
+            )
+            .unwrap();
+            request
+        });
+        (url, handle)
+    }
+}
+
+#[cfg(test)]
+fn test_client(base_url: String) -> Client {
+    Client::new(&Config {
+        base_url,
+        session: Some("secret".into()),
+    })
+    .unwrap()
+}
+
+#[test]
+fn test_submit() {
+    let (url, server) = mock::serve_once(
+        200,
+        "<article><p>That's not the right answer; your answer is too high.</p></article>",
+    );
+    let verdict = test_client(url).submit(2022, 7, Part::Two, "1234").unwrap();
+    assert_eq!(verdict, Verdict::TooHigh);
+    let request = server.join().unwrap();
+    assert!(request.starts_with("POST /2022/day/7/answer HTTP/1.1\r\n"));
+    assert!(request.contains("session=secret"));
+    assert!(request.ends_with("level=2&answer=1234"));
+}
+
+#[test]
+fn test_verdict_from_page() {
+    let verdict = |page| Verdict::from_page(page).unwrap();
+    assert_eq!(
+        verdict("That's the right answer! You are one gold star closer"),
+        Verdict::Correct
+    );
+    assert_eq!(
+        verdict("That's not the right answer. If you're stuck"),
+        Verdict::Wrong
+    );
+    assert_eq!(
+        verdict("You gave an answer too recently. You have 4m 32s left to wait."),
+        Verdict::RateLimited {
+            wait: Some("4m 32s".into())
+        }
+    );
+    assert!(Verdict::from_page("<html></html>").is_err());
+}
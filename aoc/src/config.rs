@@ -0,0 +1,59 @@
+use qu::ick_use::*;
+use serde::Deserialize;
+use std::{env, fs, path::Path};
+
+/// Default location of the config file. It holds the session token, so it isn't committed.
+pub const CONFIG_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../aoc.toml");
+
+/// Environment variable that overrides the session token in the config file.
+pub const SESSION_VAR: &str = "AOC_SESSION";
+
+/// Settings for talking to the puzzle website.
+#[derive(Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// The server to talk to, e.g. a local stand-in for testing.
+    pub base_url: String,
+    /// The value of the `session` cookie from a logged-in browser.
+    pub session: Option<String>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            base_url: "https://adventofcode.com".into(),
+            session: None,
+        }
+    }
+}
+
+impl Config {
+    /// Load the config at `path` (or the defaults if it doesn't exist), then apply any
+    /// environment overrides.
+    pub fn load(path: &Path) -> Result<Self> {
+        let mut config: Config = if path.exists() {
+            let text =
+                fs::read_to_string(path).with_context(|| format!("reading {}", path.display()))?;
+            toml::from_str(&text).with_context(|| format!("parsing {}", path.display()))?
+        } else {
+            Config::default()
+        };
+        if let Ok(session) = env::var(SESSION_VAR) {
+            config.session = Some(session);
+        }
+        Ok(config)
+    }
+
+    /// The session token, or an error explaining how to set it.
+    pub fn session(&self) -> Result<&str> {
+        match self.session.as_deref().map(str::trim) {
+            Some(session) if !session.is_empty() => Ok(session),
+            _ => bail!(
+                "no session token: set `session` in {} or the {} environment variable to the \
+                 `session` cookie from a logged-in browser",
+                CONFIG_PATH,
+                SESSION_VAR
+            ),
+        }
+    }
+}
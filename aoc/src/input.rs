@@ -0,0 +1,81 @@
+use crate::{client::Client, config::Config};
+use aoc_core::Year;
+use qu::ick_use::*;
+use std::{
+    fs,
+    io::{self, Read},
+    path::{Path, PathBuf},
+    process,
+};
+
+/// The default location of the input for `day`, i.e. `input/<day>` in the year's crate.
+pub fn default_path(year: &Year, day: u8) -> PathBuf {
+    year.input_dir().join(day.to_string())
+}
+
+/// Load the puzzle input for `day` of `year`.
+///
+/// If `path` is `None` the input is read from `input/<day>`. A path of `-` reads from stdin.
+pub fn load(year: &Year, day: u8, path: Option<&Path>) -> Result<String> {
+    match path {
+        Some(path) if path == Path::new("-") => {
+            let mut input = String::new();
+            io::stdin()
+                .read_to_string(&mut input)
+                .context("reading input from stdin")?;
+            Ok(input)
+        }
+        Some(path) => read(path),
+        None => read(&default_path(year, day)),
+    }
+}
+
+/// Download the input for `day` of `year` into `path`, unless it's already there.
+///
+/// Returns whether we downloaded it. An empty file (as left by `aoc new`) counts as missing. The
+/// file is written atomically, so an interrupted download never leaves a partial input behind.
+pub fn fetch(config: &Config, year: u16, day: u8, path: &Path) -> Result<bool> {
+    ensure!((1..=25).contains(&day), "day must be between 1 and 25");
+    if fs::metadata(path).is_ok_and(|meta| meta.len() > 0) {
+        return Ok(false);
+    }
+    let input = Client::new(config)?.input(year, day)?;
+    let dir = path
+        .parent()
+        .context("input path has no parent directory")?;
+    fs::create_dir_all(dir).with_context(|| format!("creating {}", dir.display()))?;
+    let file_name = path.file_name().context("input path has no file name")?;
+    let tmp = dir.join(format!(
+        ".{}.{}.tmp",
+        file_name.to_string_lossy(),
+        process::id()
+    ));
+    fs::write(&tmp, input).with_context(|| format!("writing {}", tmp.display()))?;
+    fs::rename(&tmp, path).with_context(|| format!("moving input to {}", path.display()))?;
+    Ok(true)
+}
+
+fn read(path: &Path) -> Result<String> {
+    fs::read_to_string(path).with_context(|| format!("reading input from {}", path.display()))
+}
+
+#[test]
+fn test_fetch() {
+    let dir = std::env::temp_dir().join(format!("aoc-fetch-test-{}", process::id()));
+    let path = dir.join("3");
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(&path, "").unwrap();
+    let (base_url, server) = crate::client::mock::serve_once(200, "vJrwpWtwJgWr\n");
+    let config = Config {
+        base_url,
+        session: Some("secret".into()),
+    };
+    assert!(fetch(&config, 2022, 3, &path).unwrap());
+    let request = server.join().unwrap();
+    assert!(request.starts_with("GET /2022/day/3/input HTTP/1.1\r\n"));
+    assert!(request.contains("session=secret"));
+    assert_eq!(fs::read_to_string(&path).unwrap(), "vJrwpWtwJgWr\n");
+    // the server has gone now, so this would fail if it tried to download again
+    assert!(!fetch(&config, 2022, 3, &path).unwrap());
+    fs::remove_dir_all(dir).unwrap();
+}
@@ -0,0 +1,129 @@
+//! Machine-readable output: one JSON object per line for each part we ran.
+use crate::runner::Outcome;
+use aoc_core::Part;
+use qu::ick_use::*;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::time::Duration;
+
+#[derive(Serialize, Deserialize)]
+pub struct Record {
+    year: u16,
+    day: u8,
+    part: u8,
+    /// `solved`, `unimplemented`, `panicked` or `error`.
+    status: String,
+    /// A number or a string, or null for rendered answers and parts that weren't solved.
+    answer: Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    rendered: Option<Rendered>,
+    /// The error or panic message for parts that failed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+    /// Time taken to parse and solve, in seconds.
+    duration: f64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Rendered {
+    width: usize,
+    height: usize,
+    rows: Vec<String>,
+}
+
+impl Record {
+    /// Read back a line printed by [`print`].
+    pub fn parse(line: &str) -> Result<Self> {
+        serde_json::from_str(line).with_context(|| format!("parsing result `{}`", line))
+    }
+
+    /// The answer as text mode would print it, or what went wrong if the part wasn't solved.
+    pub fn text(&self) -> String {
+        match (&self.rendered, &self.answer, &self.error) {
+            (Some(rendered), _, _) => rendered.rows.join("\n"),
+            (None, Value::String(answer), _) => answer.clone(),
+            (None, Value::Null, Some(error)) => error.clone(),
+            (None, Value::Null, None) => self.status.clone(),
+            (None, answer, _) => answer.to_string(),
+        }
+    }
+
+    /// Time taken to parse and solve.
+    pub fn duration(&self) -> Duration {
+        Duration::from_secs_f64(self.duration)
+    }
+}
+
+/// Print the result of running one part as a line of JSON.
+pub fn print(year: u16, day: u8, part: Part, outcome: &Outcome, duration: Duration) {
+    println!("{}", record(year, day, part, outcome, duration));
+}
+
+fn record(year: u16, day: u8, part: Part, outcome: &Outcome, duration: Duration) -> String {
+    let mut record = Record {
+        year,
+        day,
+        part: part.number(),
+        status: outcome.status().into(),
+        answer: Value::Null,
+        rendered: None,
+        error: None,
+        duration: duration.as_secs_f64(),
+    };
+    match outcome {
+        Outcome::Solved(run) => match run.answer.rendered() {
+            Some(rendered) => {
+                record.rendered = Some(Rendered {
+                    width: rendered
+                        .rows
+                        .iter()
+                        .map(|row| row.chars().count())
+                        .max()
+                        .unwrap_or(0),
+                    height: rendered.rows.len(),
+                    rows: rendered.rows,
+                })
+            }
+            None => {
+                let text = run.answer.to_string();
+                record.answer = if run.answer.is_number() {
+                    serde_json::from_str(&text).unwrap_or(Value::String(text))
+                } else {
+                    Value::String(text)
+                };
+            }
+        },
+        Outcome::Unimplemented => (),
+        Outcome::Panicked { .. } | Outcome::Error(_) => record.error = Some(outcome.to_string()),
+    }
+    serde_json::to_string(&record).expect("records are always serializable")
+}
+
+#[test]
+fn test_record() {
+    let outcome = Outcome::Solved(aoc_core::Run {
+        answer: Box::new(42u32),
+        parse_time: Duration::ZERO,
+        solve_time: Duration::ZERO,
+    });
+    assert_eq!(
+        record(2022, 1, Part::Two, &outcome, Duration::from_millis(1500)),
+        r#"{"year":2022,"day":1,"part":2,"status":"solved","answer":42,"duration":1.5}"#
+    );
+    let outcome = Outcome::Panicked {
+        location: "src/day1.rs:1:1".into(),
+        message: "oops".into(),
+    };
+    assert_eq!(
+        record(2022, 1, Part::One, &outcome, Duration::ZERO),
+        r#"{"year":2022,"day":1,"part":1,"status":"panicked","answer":null,"error":"panicked at src/day1.rs:1:1: oops","duration":0.0}"#
+    );
+    let parsed = Record::parse(&record(2022, 1, Part::One, &outcome, Duration::ZERO)).unwrap();
+    assert_eq!(parsed.text(), "panicked at src/day1.rs:1:1: oops");
+    let parsed = Record::parse(
+        r#"{"year":2022,"day":1,"part":2,"status":"solved","answer":42,"duration":1.5}"#,
+    )
+    .unwrap();
+    assert_eq!(parsed.text(), "42");
+    assert_eq!(parsed.duration(), Duration::from_millis(1500));
+}
@@ -0,0 +1,442 @@
+use aoc_core::{Entry, Part, Year};
+use qu::ick_use::*;
+use std::{path::PathBuf, process, str::FromStr, thread};
+
+mod answers;
+mod bench;
+mod client;
+mod config;
+mod input;
+mod json;
+mod runner;
+mod scaffold;
+mod store;
+mod submit;
+mod watch;
+
+/// Every year we have solutions for, oldest first.
+const YEARS: &[&Year] = &[&aoc2021::YEAR, &aoc2022::YEAR];
+
+#[derive(clap::Parser)]
+#[command(subcommand_negates_reqs = true)]
+struct Opt {
+    #[command(subcommand)]
+    cmd: Option<Cmd>,
+    /// The day to run, either `day` or `year/day[/part]` (e.g. `7` or `2022/7/2`).
+    #[arg(required = true)]
+    day: Option<Selector>,
+    part: Option<u8>,
+    /// The year to use (defaults to the most recent one).
+    #[arg(long, global = true)]
+    year: Option<u16>,
+    /// Read the puzzle input from this file instead of `input/<day>` (`-` for stdin).
+    #[arg(long)]
+    input: Option<PathBuf>,
+    /// Run against the `n`th example from the puzzle text instead of the real input.
+    #[arg(long, conflicts_with = "input")]
+    example: Option<usize>,
+    /// Check the answer to each example from the puzzle text first, and only run the real input
+    /// if they're all right.
+    #[arg(long, conflicts_with = "example")]
+    check_example: bool,
+    /// Report how long parsing and solving took.
+    #[arg(long)]
+    time: bool,
+    /// How to print answers.
+    #[arg(long, global = true, value_enum, default_value_t = Format::Text)]
+    format: Format,
+    /// The config file with the server and session token.
+    #[arg(long, global = true, default_value = config::CONFIG_PATH)]
+    config: PathBuf,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, clap::ValueEnum)]
+enum Format {
+    Text,
+    /// One JSON object per line for each part run, with its status, answer and duration.
+    Json,
+}
+
+#[derive(clap::Subcommand)]
+enum Cmd {
+    /// Run both parts of every implemented day and print a summary table.
+    All {
+        /// Number of parts to run at once (0 for one per CPU).
+        #[arg(short, long, default_value_t = 1)]
+        jobs: usize,
+    },
+    /// List the days that have solutions.
+    List,
+    /// Submit an answer to the website, computing it first if it isn't given.
+    Submit {
+        day: Selector,
+        part: Option<u8>,
+        /// The answer to submit (defaults to running the solution on the real input).
+        #[arg(long)]
+        answer: Option<String>,
+        /// The answers file, where correct answers are recorded.
+        #[arg(long, default_value = answers::ANSWERS_PATH)]
+        answers: PathBuf,
+        /// The file of wrong answers already submitted, which are never sent again.
+        #[arg(long, default_value = submit::GUESSES_PATH)]
+        guesses: PathBuf,
+    },
+    /// Check every day's answers against the expected answers file.
+    Verify {
+        /// Only check this day.
+        day: Option<Selector>,
+        /// Record the current answers as the expected ones, instead of checking them.
+        #[arg(long)]
+        record: bool,
+        /// The expected answers file.
+        #[arg(long, default_value = answers::ANSWERS_PATH)]
+        answers: PathBuf,
+    },
+    /// Download a day's puzzle input into `input/<day>`, unless it's already there.
+    Fetch { day: Selector },
+    /// Re-run both parts of a day whenever its source or input changes.
+    Watch { day: Selector },
+    /// Create a new day from the year's `src/day.rs` template and register it.
+    New { day: Selector },
+    /// Time a day's solution over many runs and compare against a saved baseline.
+    Bench {
+        day: Selector,
+        /// The part to benchmark (both parts if omitted).
+        part: Option<u8>,
+        /// Number of timed runs.
+        #[arg(short = 'n', long, default_value_t = 100)]
+        iterations: usize,
+        /// Read the puzzle input from this file instead of `input/<day>` (`-` for stdin).
+        #[arg(long)]
+        input: Option<PathBuf>,
+        /// The baseline file to compare against.
+        #[arg(long, default_value = bench::BASELINE_PATH)]
+        baseline: PathBuf,
+        /// Save these results as the new baseline.
+        #[arg(long)]
+        save: bool,
+    },
+    /// Print a day's report on its input, for the days that have one.
+    Analyse {
+        day: Selector,
+        /// Read the puzzle input from this file instead of `input/<day>` (`-` for stdin).
+        #[arg(long)]
+        input: Option<PathBuf>,
+    },
+}
+
+#[qu::ick]
+fn main(opt: Opt) -> Result {
+    let format = opt.format;
+    let result = run(opt);
+    // parts that didn't produce an answer get their own exit codes
+    if let Some(failed) = result
+        .as_ref()
+        .err()
+        .and_then(|e| e.downcast_ref::<runner::Failed>())
+    {
+        // the JSON records already say why, and stdout must only have records on it
+        if format == Format::Text {
+            event!(Level::ERROR, "{}", failed);
+        }
+        process::exit(failed.code);
+    }
+    result
+}
+
+fn run(opt: Opt) -> Result {
+    match opt.cmd {
+        Some(Cmd::All { jobs }) => {
+            let jobs = match jobs {
+                0 => thread::available_parallelism().map_or(1, |n| n.get()),
+                n => n,
+            };
+            return runner::run_all(&years(opt.year)?, opt.format, jobs);
+        }
+        Some(Cmd::List) => {
+            for year in years(opt.year)? {
+                for entry in year.days {
+                    println!("{}/{}", year.year, entry.day);
+                }
+            }
+            return Ok(());
+        }
+        Some(Cmd::Bench {
+            day,
+            part,
+            iterations,
+            input,
+            baseline: baseline_path,
+            save,
+        }) => {
+            let (year, entry, part) = day.resolve(opt.year, part)?;
+            let input = input::load(year, entry.day, input.as_deref())?;
+            let parts = match part {
+                Some(part) => vec![part],
+                None => vec![Part::One, Part::Two],
+            };
+            let mut baseline = bench::Baseline::load(&baseline_path)?;
+            let mut regressed = false;
+            for part in parts {
+                let result = bench::bench(entry, &input, part, iterations)?;
+                regressed |= bench::report(
+                    year.year,
+                    entry.day,
+                    part,
+                    &result,
+                    baseline.get(year.year, entry.day, part),
+                );
+                baseline.insert(year.year, entry.day, part, result);
+            }
+            if save {
+                baseline.save(&baseline_path)?;
+            } else if regressed {
+                bail!("performance regressed compared to baseline");
+            }
+            return Ok(());
+        }
+        Some(Cmd::Analyse { day, input }) => {
+            ensure!(day.part.is_none(), "`analyse` takes a day, not a part");
+            let (year, entry, _) = day.resolve(opt.year, None)?;
+            let input = input::load(year, entry.day, input.as_deref())?;
+            let report = entry
+                .solution
+                .analyse(&input)?
+                .with_context(|| format!("{}/{} has no analysis", year.year, entry.day))?;
+            print!("{}", report);
+            return Ok(());
+        }
+        Some(Cmd::Verify {
+            day,
+            record,
+            answers: answers_path,
+        }) => {
+            let selected = match day {
+                Some(day) => {
+                    let (year, entry, _) = day.resolve(opt.year, None)?;
+                    vec![(year, std::slice::from_ref(entry))]
+                }
+                None => years(opt.year)?
+                    .into_iter()
+                    .map(|year| (year, year.days))
+                    .collect(),
+            };
+            let mut answers = answers::Answers::load(&answers_path)?;
+            let bad = selected
+                .iter()
+                .map(|(year, days)| answers::verify(year, days, &mut answers, record))
+                .sum::<usize>();
+            if record {
+                answers.save(&answers_path)?;
+            }
+            if bad > 0 {
+                bail!("{} part(s) did not match the expected answer", bad);
+            }
+            return Ok(());
+        }
+        Some(Cmd::Submit {
+            day,
+            part,
+            answer,
+            answers: answers_path,
+            guesses: guesses_path,
+        }) => {
+            let (year, entry, part) = day.resolve(opt.year, part)?;
+            let part = part.context("must specify part 1 or part 2")?;
+            let answer = match answer {
+                Some(answer) => answer,
+                None => {
+                    let input = input::load(year, entry.day, None)?;
+                    match runner::run_part(entry, &input, part).0 {
+                        runner::Outcome::Solved(run) => {
+                            ensure!(
+                                run.answer.rendered().is_none(),
+                                "this answer is drawn, so read it and pass it with --answer:\n{}",
+                                run.answer
+                            );
+                            run.answer.to_string()
+                        }
+                        outcome => return Err(runner::Failed::from(outcome).into()),
+                    }
+                }
+            };
+            let config = config::Config::load(&opt.config)?;
+            return submit::submit(
+                &config,
+                &answers_path,
+                &guesses_path,
+                year.year,
+                entry.day,
+                part,
+                &answer,
+            );
+        }
+        Some(Cmd::Fetch { day }) => {
+            ensure!(day.part.is_none(), "`fetch` takes a day, not a part");
+            let year = find_year(day.year.or(opt.year))?;
+            let path = input::default_path(year, day.day);
+            let config = config::Config::load(&opt.config)?;
+            if input::fetch(&config, year.year, day.day, &path)? {
+                println!("downloaded {}", path.display());
+            } else {
+                println!("{} already exists", path.display());
+            }
+            return Ok(());
+        }
+        Some(Cmd::Watch { day }) => {
+            ensure!(
+                day.part.is_none(),
+                "`watch` runs both parts, so takes a day"
+            );
+            let (year, entry, _) = day.resolve(opt.year, None)?;
+            return watch::watch(year, entry.day);
+        }
+        Some(Cmd::New { day }) => {
+            ensure!(day.part.is_none(), "`new` takes a day, not a part");
+            return scaffold::new_day(find_year(day.year.or(opt.year))?, day.day);
+        }
+        None => (),
+    }
+    let (year, entry, part) = opt
+        .day
+        .context("must specify a day")?
+        .resolve(opt.year, opt.part)?;
+    let part = part.context("must specify part 1 or part 2")?;
+    let example = opt.example.map(|n| entry.example(n)).transpose()?;
+    if opt.check_example {
+        runner::check_examples(entry, part, opt.format == Format::Text)?;
+    }
+    let input = match example {
+        Some(example) => example.input.to_owned(),
+        None => input::load(year, entry.day, opt.input.as_deref())?,
+    };
+    let (outcome, time) = runner::run_part(entry, &input, part);
+    if opt.format == Format::Json {
+        json::print(year.year, entry.day, part, &outcome, time);
+    }
+    let run = match outcome {
+        runner::Outcome::Solved(run) => run,
+        outcome => return Err(runner::Failed::from(outcome).into()),
+    };
+    if opt.format == Format::Json {
+        return Ok(());
+    }
+    let answer = run.answer.to_string();
+    if opt.check_example {
+        println!("{}", runner::labelled("input", &answer));
+    } else {
+        println!("{}", answer);
+    }
+    if let Some(expected) = example.and_then(|example| example.expected(part)) {
+        if answer != expected {
+            event!(Level::WARN, "example answer should be {}", expected);
+        }
+    }
+    if opt.time {
+        eprintln!(
+            "parse: {:.2?}, solve: {:.2?}",
+            run.parse_time, run.solve_time
+        );
+    }
+    Ok(())
+}
+
+/// Find the solutions for `year`, or the most recent year if it's `None`.
+fn find_year(year: Option<u16>) -> Result<&'static Year> {
+    match year {
+        Some(year) => YEARS
+            .iter()
+            .copied()
+            .find(|y| y.year == year)
+            .with_context(|| format!("no solutions for {}", year)),
+        None => Ok(YEARS[YEARS.len() - 1]),
+    }
+}
+
+/// The selected year, or all years if `year` is `None`.
+fn years(year: Option<u16>) -> Result<Vec<&'static Year>> {
+    match year {
+        Some(_) => Ok(vec![find_year(year)?]),
+        None => Ok(YEARS.to_vec()),
+    }
+}
+
+/// Selects a day, written as `day`, `year/day` or `year/day/part`.
+#[derive(Debug, Copy, Clone)]
+struct Selector {
+    year: Option<u16>,
+    day: u8,
+    part: Option<u8>,
+}
+
+impl Selector {
+    /// Look up the selected day, filling in the year and part from the other arguments if the
+    /// selector didn't include them.
+    fn resolve(
+        self,
+        year: Option<u16>,
+        part: Option<u8>,
+    ) -> Result<(&'static Year, &'static Entry, Option<Part>)> {
+        let year = match (self.year, year) {
+            (Some(a), Some(b)) if a != b => bail!("conflicting years {} and {}", a, b),
+            (a, b) => find_year(a.or(b))?,
+        };
+        let part = match (self.part, part) {
+            (Some(a), Some(b)) if a != b => bail!("conflicting parts {} and {}", a, b),
+            (a, b) => a.or(b).map(Part::from_number).transpose()?,
+        };
+        Ok((year, year.find(self.day)?, part))
+    }
+}
+
+impl FromStr for Selector {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let fields = s
+            .split('/')
+            .map(str::parse::<u16>)
+            .collect::<Result<Vec<_>, _>>()
+            .context("expected `day`, `year/day` or `year/day/part`")?;
+        let day = |day: u16| u8::try_from(day).context("day out of range");
+        let part = |part: u16| u8::try_from(part).context("part out of range");
+        Ok(match fields[..] {
+            [d] => Selector {
+                year: None,
+                day: day(d)?,
+                part: None,
+            },
+            [y, d] => Selector {
+                year: Some(y),
+                day: day(d)?,
+                part: None,
+            },
+            [y, d, p] => Selector {
+                year: Some(y),
+                day: day(d)?,
+                part: Some(part(p)?),
+            },
+            _ => bail!("expected `day`, `year/day` or `year/day/part`"),
+        })
+    }
+}
+
+#[test]
+fn test_parse_args() {
+    let opt = Opt::try_parse_from(["aoc", "--year", "2022", "all"]).unwrap();
+    assert!(matches!(opt.cmd, Some(Cmd::All { jobs: 1 })));
+    assert_eq!(opt.year, Some(2022));
+    let opt = Opt::try_parse_from(["aoc", "--format", "json", "all"]).unwrap();
+    assert!(matches!(opt.cmd, Some(Cmd::All { .. })));
+    assert_eq!(opt.format, Format::Json);
+    let opt = Opt::try_parse_from(["aoc", "--year", "2021", "list"]).unwrap();
+    assert!(matches!(opt.cmd, Some(Cmd::List)));
+    let opt = Opt::try_parse_from(["aoc", "all", "--year", "2021"]).unwrap();
+    assert_eq!(opt.year, Some(2021));
+
+    let opt = Opt::try_parse_from(["aoc", "--year", "2021", "7", "2"]).unwrap();
+    assert!(opt.cmd.is_none());
+    assert_eq!(opt.day.unwrap().day, 7);
+    assert_eq!(opt.part, Some(2));
+    assert!(Opt::try_parse_from(["aoc", "--time"]).is_err());
+}
@@ -0,0 +1,365 @@
+use crate::{input, json, Format};
+use aoc_core::{Entry, Part, Run, Year};
+use qu::ick_use::*;
+use std::{
+    any::Any,
+    cell::{Cell, RefCell},
+    fmt,
+    panic::{self, AssertUnwindSafe},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex, Once,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+/// Exit code when a part returned an error.
+pub const EXIT_ERROR: i32 = 1;
+/// Exit code when a part hit a `todo!()` or `unimplemented!()`.
+pub const EXIT_UNIMPLEMENTED: i32 = 3;
+/// Exit code when a part panicked, the same as an uncaught panic.
+pub const EXIT_PANICKED: i32 = 101;
+
+/// What happened when we tried to run one part of a day.
+pub enum Outcome {
+    Solved(Run),
+    /// The solution hit a `todo!()` or `unimplemented!()`.
+    Unimplemented,
+    Panicked {
+        /// Where the panic happened, as `file:line:column`.
+        location: String,
+        message: String,
+    },
+    /// The solution returned an error (or we couldn't load the input), with its whole chain of
+    /// causes.
+    Error(String),
+}
+
+impl Outcome {
+    /// The status to report for machine-readable output.
+    pub fn status(&self) -> &'static str {
+        match self {
+            Outcome::Solved(_) => "solved",
+            Outcome::Unimplemented => "unimplemented",
+            Outcome::Panicked { .. } => "panicked",
+            Outcome::Error(_) => "error",
+        }
+    }
+
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            Outcome::Solved(_) => 0,
+            Outcome::Unimplemented => EXIT_UNIMPLEMENTED,
+            Outcome::Panicked { .. } => EXIT_PANICKED,
+            Outcome::Error(_) => EXIT_ERROR,
+        }
+    }
+
+    /// Did the part panic or return an error (rather than just not being written yet)?
+    pub fn is_failure(&self) -> bool {
+        matches!(self, Outcome::Panicked { .. } | Outcome::Error(_))
+    }
+
+    pub fn error(e: &Error) -> Self {
+        Outcome::Error(format!("{:#}", e))
+    }
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Outcome::Solved(run) => run.answer.fmt(f),
+            Outcome::Unimplemented => f.write_str("unimplemented"),
+            Outcome::Panicked { location, message } => {
+                write!(f, "panicked at {}: {}", location, message)
+            }
+            Outcome::Error(chain) => write!(f, "error: {}", chain),
+        }
+    }
+}
+
+/// Returned when a part didn't produce an answer, so `main` can exit with the right code.
+#[derive(Debug)]
+pub struct Failed {
+    pub code: i32,
+    pub message: String,
+}
+
+impl fmt::Display for Failed {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for Failed {}
+
+impl From<Outcome> for Failed {
+    fn from(outcome: Outcome) -> Self {
+        Failed {
+            code: outcome.exit_code(),
+            message: outcome.to_string(),
+        }
+    }
+}
+
+thread_local! {
+    /// Set while this thread is running a part, so the panic hook records panics instead of
+    /// printing them.
+    static CAPTURING: Cell<bool> = const { Cell::new(false) };
+    /// The location of the last panic recorded on this thread.
+    static PANIC_LOCATION: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+/// Install a panic hook that records where panics happen while a part is running, and leaves
+/// other panics to the default hook.
+fn install_panic_hook() {
+    static INSTALL: Once = Once::new();
+    INSTALL.call_once(|| {
+        let default = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            if CAPTURING.get() {
+                let location = info
+                    .location()
+                    .map_or_else(|| "<unknown>".into(), ToString::to_string);
+                PANIC_LOCATION.set(Some(location));
+            } else {
+                default(info);
+            }
+        }));
+    });
+}
+
+/// Run one part of `entry` against `input`, catching any panics.
+pub fn run_part(entry: &Entry, input: &str, part: Part) -> (Outcome, Duration) {
+    install_panic_hook();
+    CAPTURING.set(true);
+    let start = Instant::now();
+    let result = panic::catch_unwind(AssertUnwindSafe(|| entry.solution.run(input, part)));
+    let elapsed = start.elapsed();
+    CAPTURING.set(false);
+    let outcome = match result {
+        Ok(Ok(run)) => Outcome::Solved(run),
+        Ok(Err(e)) => Outcome::error(&e),
+        Err(payload) => match panic_message(&*payload) {
+            msg if msg.starts_with("not yet implemented") || msg.starts_with("not implemented") => {
+                Outcome::Unimplemented
+            }
+            msg => Outcome::Panicked {
+                location: PANIC_LOCATION.take().unwrap_or_else(|| "<unknown>".into()),
+                message: msg.into(),
+            },
+        },
+    };
+    (outcome, elapsed)
+}
+
+/// Run `part` against each of `entry`'s examples that has a known answer for it, printing the
+/// answers if `print` is set. Fails unless there was at least one to check and they all matched.
+pub fn check_examples(entry: &Entry, part: Part, print: bool) -> Result {
+    let mut checked = 0;
+    for (idx, example) in entry.solution.examples().iter().enumerate() {
+        let Some(expected) = example.expected(part) else {
+            continue;
+        };
+        let label = format!("example {}", idx + 1);
+        let answer = match run_part(entry, example.input, part).0 {
+            Outcome::Solved(run) => run.answer.to_string(),
+            outcome => {
+                return Err(Failed {
+                    code: outcome.exit_code(),
+                    message: format!("{}: {}", label, outcome),
+                }
+                .into())
+            }
+        };
+        if print {
+            println!("{}", labelled(&label, &answer));
+        }
+        ensure!(
+            answer == expected,
+            "{} should be {}, so not running the real input",
+            label,
+            expected
+        );
+        checked += 1;
+    }
+    ensure!(
+        checked > 0,
+        "day {} has no example with a known part {} answer to check",
+        entry.day,
+        part
+    );
+    Ok(())
+}
+
+/// `answer` after `label`, or on the lines below it if it's drawn.
+pub fn labelled(label: &str, answer: &str) -> String {
+    match answer.contains('\n') {
+        true => format!("{}:\n{}", label, answer),
+        false => format!("{}: {}", label, answer),
+    }
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> &str {
+    if let Some(msg) = payload.downcast_ref::<&str>() {
+        msg
+    } else if let Some(msg) = payload.downcast_ref::<String>() {
+        msg
+    } else {
+        "<non-string panic payload>"
+    }
+}
+
+/// Run `f` on each of `tasks` using `jobs` threads, and return the results in the same order as
+/// `tasks`.
+fn run_parallel<T: Sync, R: Send>(tasks: &[T], jobs: usize, f: impl Fn(&T) -> R + Sync) -> Vec<R> {
+    let next = AtomicUsize::new(0);
+    let results = Mutex::new(tasks.iter().map(|_| None).collect::<Vec<_>>());
+    thread::scope(|s| {
+        for _ in 0..jobs.clamp(1, tasks.len().max(1)) {
+            s.spawn(|| loop {
+                let idx = next.fetch_add(1, Ordering::Relaxed);
+                let Some(task) = tasks.get(idx) else {
+                    break;
+                };
+                let result = f(task);
+                results.lock().unwrap()[idx] = Some(result);
+            });
+        }
+    });
+    results
+        .into_inner()
+        .unwrap()
+        .into_iter()
+        .map(|result| result.expect("every task was run"))
+        .collect()
+}
+
+/// Run both parts of every day in `years`, and print a table of the results (or a JSON record
+/// for each part).
+///
+/// Parts are run on `jobs` threads, but always reported in order. Returns a [`Failed`] error if
+/// any part panicked or returned an error (unimplemented parts are not counted as failures).
+pub fn run_all(years: &[&Year], format: Format, jobs: usize) -> Result {
+    let inputs = years
+        .iter()
+        .flat_map(|year| {
+            year.days
+                .iter()
+                .map(move |entry| (year, entry, input::load(year, entry.day, None)))
+        })
+        .collect::<Vec<_>>();
+    let tasks = inputs
+        .iter()
+        .flat_map(|(year, entry, input)| {
+            [Part::One, Part::Two].map(|part| (year, entry, input, part))
+        })
+        .collect::<Vec<_>>();
+    let rows = run_parallel(&tasks, jobs, |&(year, entry, input, part)| {
+        let (outcome, time) = match input {
+            Ok(input) => run_part(entry, input, part),
+            Err(e) => (Outcome::error(e), Duration::ZERO),
+        };
+        (year.year, entry.day, part, outcome, time)
+    });
+    let failures = rows
+        .iter()
+        .map(|(_, _, _, outcome, _)| outcome)
+        .filter(|outcome| outcome.is_failure())
+        .collect::<Vec<_>>();
+    let result = match failures.iter().map(|outcome| outcome.exit_code()).max() {
+        Some(code) => Err(Failed {
+            code,
+            message: format!("{} part(s) failed", failures.len()),
+        }
+        .into()),
+        None => Ok(()),
+    };
+
+    if format == Format::Json {
+        for (year, day, part, outcome, time) in &rows {
+            json::print(*year, *day, *part, outcome, *time);
+        }
+        return result;
+    }
+
+    let answers = rows
+        .iter()
+        .map(|(_, _, _, outcome, _)| outcome.to_string())
+        .collect::<Vec<_>>();
+    let width = answers
+        .iter()
+        .flat_map(|answer| answer.lines())
+        .map(str::len)
+        .chain(["answer".len()])
+        .max()
+        .unwrap();
+    println!("year day part {:<width$} {:>12}", "answer", "time");
+    for ((year, day, part, _, time), answer) in rows.iter().zip(&answers) {
+        let mut lines = answer.lines();
+        let time = format!("{:.2?}", time);
+        println!(
+            "{:>4} {:>3} {:>4} {:<width$} {:>12}",
+            year,
+            day,
+            part,
+            lines.next().unwrap_or(""),
+            time
+        );
+        // multi-line answers (e.g. day 10's screen) continue on the following rows
+        for line in lines {
+            println!("{:>4} {:>3} {:>4} {}", "", "", "", line);
+        }
+    }
+    result
+}
+
+#[test]
+fn test_run_parallel() {
+    let tasks = (0..50).collect::<Vec<u64>>();
+    let results = run_parallel(&tasks, 4, |n| {
+        thread::sleep(Duration::from_micros(50 - n));
+        n * 2
+    });
+    assert_eq!(results, tasks.iter().map(|n| n * 2).collect::<Vec<_>>());
+}
+
+#[test]
+fn test_check_examples() {
+    check_examples(aoc2022::YEAR.find(1).unwrap(), Part::Two, false).unwrap();
+    let err = check_examples(aoc2022::YEAR.find(11).unwrap(), Part::One, false).unwrap_err();
+    let failed = err.downcast_ref::<Failed>().unwrap();
+    assert_eq!(failed.code, EXIT_UNIMPLEMENTED);
+    assert_eq!(failed.message, "example 1: unimplemented");
+}
+
+#[test]
+fn test_examples() {
+    let mut failures = vec![];
+    for year in crate::YEARS {
+        for entry in year.days {
+            for (idx, example) in entry.solution.examples().iter().enumerate() {
+                for part in [Part::One, Part::Two] {
+                    let Some(expected) = example.expected(part) else {
+                        continue;
+                    };
+                    match run_part(entry, example.input, part).0 {
+                        Outcome::Solved(run) if run.answer.to_string() == expected => (),
+                        Outcome::Unimplemented => (),
+                        outcome => failures.push(format!(
+                            "{} day {} example {} part {}: expected {:?}, got {:?}",
+                            year.year,
+                            entry.day,
+                            idx + 1,
+                            part,
+                            expected,
+                            outcome.to_string()
+                        )),
+                    }
+                }
+            }
+        }
+    }
+    assert!(failures.is_empty(), "{}", failures.join("\n"));
+}
@@ -0,0 +1,150 @@
+use crate::input;
+use aoc_core::Year;
+use qu::ick_use::*;
+use std::{
+    fs::{self, OpenOptions},
+    io::Write,
+    path::Path,
+};
+
+/// The line that opens the day registry in the year's `lib.rs`.
+const REGISTRY_START: &str = "aoc_core::days! {";
+
+/// Create `src/day<day>.rs` from the `src/day.rs` template in the year's crate, declare it and add
+/// it to the registry in `lib.rs`, and create an empty input file for it.
+pub fn new_day(year: &Year, day: u8) -> Result {
+    ensure!((1..=25).contains(&day), "day must be between 1 and 25");
+    ensure!(
+        !year.days.iter().any(|entry| entry.day == day),
+        "day {} of {} is already registered",
+        day,
+        year.year
+    );
+    let src = year.src_dir();
+    let module_path = src.join(format!("day{}.rs", day));
+    ensure!(
+        !module_path.exists(),
+        "{} already exists",
+        module_path.display()
+    );
+
+    let template = read(&src.join("day.rs"))?;
+    let lib_path = src.join("lib.rs");
+    let lib = register(&read(&lib_path)?, day)?;
+
+    // `create_new` so we never clobber a day that appeared since we checked.
+    OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(&module_path)
+        .and_then(|mut file| file.write_all(render(&template, year.year, day).as_bytes()))
+        .with_context(|| format!("writing {}", module_path.display()))?;
+    println!("created {}", module_path.display());
+    fs::write(&lib_path, lib).with_context(|| format!("writing {}", lib_path.display()))?;
+    println!("registered day {} in {}", day, lib_path.display());
+
+    let input_path = input::default_path(year, day);
+    if !input_path.exists() {
+        fs::write(&input_path, "").with_context(|| format!("writing {}", input_path.display()))?;
+        println!("created {}", input_path.display());
+    }
+    Ok(())
+}
+
+fn read(path: &Path) -> Result<String> {
+    fs::read_to_string(path).with_context(|| format!("reading {}", path.display()))
+}
+
+/// Fill in the placeholders in the day template.
+fn render(template: &str, year: u16, day: u8) -> String {
+    template
+        .replace("{{year}}", &year.to_string())
+        .replace("{{day}}", &day.to_string())
+}
+
+/// Declare `day`'s module in the source of `lib.rs` and add it to the `days!` registry, keeping
+/// both in day order.
+fn register(lib: &str, day: u8) -> Result<String> {
+    let mut lines = lib.lines().map(str::to_owned).collect::<Vec<_>>();
+    let start = lines
+        .iter()
+        .position(|line| line.trim() == REGISTRY_START)
+        .context("couldn't find the day registry in lib.rs")?;
+    let end = start
+        + lines[start..]
+            .iter()
+            .position(|line| line.trim() == "}")
+            .context("couldn't find the end of the day registry in lib.rs")?;
+    // entries look like `    11 => day11,`
+    let entry_day = |line: &str| {
+        line.trim()
+            .split_once(" => ")
+            .and_then(|(n, _)| n.parse::<u8>().ok())
+    };
+    let insert_at = lines[start + 1..end]
+        .iter()
+        .position(|line| entry_day(line).is_some_and(|n| n > day))
+        .map_or(end, |idx| start + 1 + idx);
+    lines.insert(insert_at, format!("    {} => day{},", day, day));
+
+    // module declarations look like `mod day11;`, and come before the registry
+    let module_day = |line: &str| {
+        line.strip_prefix("mod day")
+            .and_then(|rest| rest.strip_suffix(';'))
+            .and_then(|n| n.parse::<u8>().ok())
+    };
+    let modules = lines[..start]
+        .iter()
+        .enumerate()
+        .filter_map(|(idx, line)| Some((idx, module_day(line)?)))
+        .collect::<Vec<_>>();
+    let insert_at = match modules.iter().find(|&&(_, n)| n > day) {
+        Some(&(idx, _)) => idx,
+        None => match modules.last() {
+            Some(&(idx, _)) => idx + 1,
+            // the first day, so give the modules their own paragraph
+            None => {
+                lines.insert(start, String::new());
+                start
+            }
+        },
+    };
+    lines.insert(insert_at, format!("mod day{};", day));
+
+    let mut out = lines.join("\n");
+    out.push('\n');
+    Ok(out)
+}
+
+#[test]
+fn test_register() {
+    let lib =
+        "mod day1;\nmod day3;\n\naoc_core::days! {\n    2022;\n    1 => day1,\n    3 => day3,\n}\n";
+    assert_eq!(
+        register(lib, 2).unwrap(),
+        "mod day1;\nmod day2;\nmod day3;\n\n\
+         aoc_core::days! {\n    2022;\n    1 => day1,\n    2 => day2,\n    3 => day3,\n}\n"
+    );
+    assert_eq!(
+        register(lib, 12).unwrap(),
+        "mod day1;\nmod day3;\nmod day12;\n\n\
+         aoc_core::days! {\n    2022;\n    1 => day1,\n    3 => day3,\n    12 => day12,\n}\n"
+    );
+    assert_eq!(
+        register("aoc_core::days! {\n    2022;\n}\n", 1).unwrap(),
+        "mod day1;\n\naoc_core::days! {\n    2022;\n    1 => day1,\n}\n"
+    );
+    assert!(register("pub fn f() {}", 1).is_err());
+}
+
+#[test]
+fn test_render() {
+    assert_eq!(
+        render(
+            "//! https://adventofcode.com/{{year}}/day/{{day}}",
+            2022,
+            12
+        ),
+        "//! https://adventofcode.com/2022/day/12"
+    );
+}
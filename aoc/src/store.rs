@@ -0,0 +1,82 @@
+//! The TOML files we keep results in, which all hold a value per part.
+use aoc_core::Part;
+use qu::ick_use::*;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::{collections::BTreeMap, fs, path::Path};
+
+/// Values keyed by year, day then part (e.g. `store["2022"]["7"]["part1"]`).
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Store<T>(BTreeMap<String, BTreeMap<String, BTreeMap<String, T>>>);
+
+impl<T> Default for Store<T> {
+    fn default() -> Self {
+        Store(BTreeMap::new())
+    }
+}
+
+impl<T: Serialize + DeserializeOwned> Store<T> {
+    /// Load the store at `path`, or an empty one if the file doesn't exist.
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let text =
+            fs::read_to_string(path).with_context(|| format!("reading {}", path.display()))?;
+        toml::from_str(&text).with_context(|| format!("parsing {}", path.display()))
+    }
+
+    pub fn save(&self, path: &Path) -> Result {
+        fs::write(path, toml::to_string(self)?)
+            .with_context(|| format!("writing {}", path.display()))
+    }
+}
+
+impl<T> Store<T> {
+    pub fn get(&self, year: u16, day: u8, part: Part) -> Option<&T> {
+        self.0
+            .get(&year.to_string())?
+            .get(&day.to_string())?
+            .get(&part_key(part))
+    }
+
+    pub fn insert(&mut self, year: u16, day: u8, part: Part, value: T) {
+        self.0
+            .entry(year.to_string())
+            .or_default()
+            .entry(day.to_string())
+            .or_default()
+            .insert(part_key(part), value);
+    }
+
+    /// The value for `part`, inserting the default if there isn't one yet.
+    pub fn entry(&mut self, year: u16, day: u8, part: Part) -> &mut T
+    where
+        T: Default,
+    {
+        self.0
+            .entry(year.to_string())
+            .or_default()
+            .entry(day.to_string())
+            .or_default()
+            .entry(part_key(part))
+            .or_default()
+    }
+}
+
+fn part_key(part: Part) -> String {
+    format!("part{}", part)
+}
+
+#[test]
+fn test_store() {
+    let mut store = Store::default();
+    store.insert(2022, 7, Part::One, vec!["10".to_owned()]);
+    store.entry(2022, 7, Part::One).push("20".to_owned());
+    store.entry(2022, 7, Part::Two).push("30".to_owned());
+    let text = toml::to_string(&store).unwrap();
+    assert!(text.contains("part1 = [\"10\", \"20\"]"));
+    let store: Store<Vec<String>> = toml::from_str(&text).unwrap();
+    assert_eq!(store.get(2022, 7, Part::One).unwrap(), &["10", "20"]);
+    assert!(store.get(2022, 8, Part::One).is_none());
+}
@@ -0,0 +1,114 @@
+use crate::{
+    answers::Answers,
+    client::{Client, Verdict},
+    config::Config,
+    store::Store,
+};
+use aoc_core::Part;
+use qu::ick_use::*;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Default location of the file of wrong answers we've already submitted.
+pub const GUESSES_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../guesses.toml");
+
+/// Answers the website has told us are wrong, keyed by year, day then part.
+pub type Guesses = Store<Vec<Guess>>;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Guess {
+    pub answer: String,
+    /// What the website said, e.g. "too high".
+    pub verdict: String,
+}
+
+/// Submit `answer` to the server in `config`, unless we already know whether it's right.
+///
+/// Wrong answers are remembered in the guesses file at `guesses_path` so they're never sent again,
+/// and correct ones are recorded in the answers file at `answers_path`. Returns an error unless the
+/// answer was correct.
+pub fn submit(
+    config: &Config,
+    answers_path: &Path,
+    guesses_path: &Path,
+    year: u16,
+    day: u8,
+    part: Part,
+    answer: &str,
+) -> Result {
+    ensure!(
+        !answer.trim().is_empty(),
+        "refusing to submit an empty answer"
+    );
+    let mut answers = Answers::load(answers_path)?;
+    match answers.get(year, day, part) {
+        Some(correct) if *correct == answer => {
+            println!("{} is already known to be correct", answer);
+            return Ok(());
+        }
+        Some(correct) => bail!(
+            "not submitting {}: the correct answer is already known to be {}",
+            answer,
+            correct
+        ),
+        None => (),
+    }
+    let mut guesses = Guesses::load(guesses_path)?;
+    let earlier = guesses
+        .get(year, day, part)
+        .and_then(|guesses| guesses.iter().find(|guess| guess.answer == answer));
+    if let Some(guess) = earlier {
+        bail!(
+            "not submitting {}: it was already submitted and was {}",
+            answer,
+            guess.verdict
+        );
+    }
+
+    let verdict = Client::new(config)?.submit(year, day, part, answer)?;
+    println!("{}", verdict);
+    if verdict.is_wrong() {
+        guesses.entry(year, day, part).push(Guess {
+            answer: answer.to_owned(),
+            verdict: verdict.to_string(),
+        });
+        guesses.save(guesses_path)?;
+    } else if verdict == Verdict::Correct {
+        answers.insert(year, day, part, answer.to_owned());
+        answers.save(answers_path)?;
+        return Ok(());
+    }
+    bail!("{} was not accepted: {}", answer, verdict)
+}
+
+#[test]
+fn test_submit_twice() {
+    use crate::client::mock;
+    use std::{fs, process};
+
+    let dir = std::env::temp_dir().join(format!("aoc-submit-test-{}", process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let answers = dir.join("answers.toml");
+    let guesses = dir.join("guesses.toml");
+    let (base_url, server) = mock::serve_once(
+        200,
+        "<article><p>That's not the right answer; your answer is too low.</p></article>",
+    );
+    let config = Config {
+        base_url,
+        session: Some("secret".into()),
+    };
+    let submit = || submit(&config, &answers, &guesses, 2022, 7, Part::One, "1234");
+
+    let err = submit().unwrap_err();
+    assert_eq!(err.to_string(), "1234 was not accepted: too low");
+    assert!(server.join().unwrap().ends_with("level=1&answer=1234"));
+    // the server has gone now, so this would fail differently if it tried to submit again
+    let err = submit().unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "not submitting 1234: it was already submitted and was too low"
+    );
+    assert!(!answers.exists());
+    fs::remove_dir_all(dir).unwrap();
+}
@@ -0,0 +1,158 @@
+//! Re-run a day whenever its source or input changes, showing how the answers moved.
+use crate::{input, json::Record};
+use aoc_core::{Part, Year};
+use notify::{Event, EventKind, RecursiveMode, Watcher};
+use qu::ick_use::*;
+use std::{
+    env, fs,
+    path::Path,
+    process::Command,
+    sync::mpsc,
+    thread,
+    time::{Duration, Instant},
+};
+
+/// How long to wait for further changes after the first one, so that an editor touching a file
+/// several times on save only triggers one run.
+const SETTLE_TIME: Duration = Duration::from_millis(100);
+
+/// Watch `src/day<day>.rs` and `input/<day>`, rebuilding and running both parts each time either
+/// changes. Runs until interrupted.
+pub fn watch(year: &Year, day: u8) -> Result {
+    let source = year.src_dir().join(format!("day{}.rs", day));
+    let input = input::default_path(year, day);
+    let (tx, rx) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(tx).context("starting file watcher")?;
+    // watch the directories rather than the files, so we still see changes after an editor saves
+    // by replacing the file
+    for dir in [year.src_dir(), year.input_dir()] {
+        fs::create_dir_all(&dir).with_context(|| format!("creating {}", dir.display()))?;
+        watcher
+            .watch(&dir, RecursiveMode::NonRecursive)
+            .with_context(|| format!("watching {}", dir.display()))?;
+    }
+    let mut previous = [None, None];
+    loop {
+        print!("\x1b[2J\x1b[H");
+        println!("watching {} and {}", source.display(), input.display());
+        let start = Instant::now();
+        match build(year) {
+            Ok(()) => {
+                for (part, previous) in [Part::One, Part::Two].into_iter().zip(&mut previous) {
+                    match run_part(year, day, part) {
+                        Ok(record) => {
+                            let text = record.text();
+                            show(part, &text, previous.as_deref(), record.duration());
+                            *previous = Some(text);
+                        }
+                        Err(e) => println!("part {}: {:#}", part.number(), e),
+                    }
+                }
+                println!("finished in {:.2?}", start.elapsed());
+            }
+            Err(e) => println!("{:#}", e),
+        }
+
+        loop {
+            let event = rx
+                .recv()
+                .context("file watcher stopped")?
+                .context("watching for changes")?;
+            if touches(&event, &[&source, &input]) {
+                break;
+            }
+        }
+        thread::sleep(SETTLE_TIME);
+        while rx.try_recv().is_ok() {}
+    }
+}
+
+/// Whether `event` changed any of `files`.
+fn touches(event: &Event, files: &[&Path]) -> bool {
+    matches!(
+        event.kind,
+        EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_)
+    ) && event
+        .paths
+        .iter()
+        .any(|path| files.contains(&path.as_path()))
+}
+
+/// A cargo command, run from the year's crate.
+fn cargo(year: &Year) -> Command {
+    let mut cargo = Command::new(env::var_os("CARGO").unwrap_or_else(|| "cargo".into()));
+    cargo.current_dir(year.dir);
+    cargo
+}
+
+/// Rebuild the runner with the latest source.
+fn build(year: &Year) -> Result {
+    let output = cargo(year)
+        .args(["build", "--quiet", "--color", "always", "--package", "aoc"])
+        .output()
+        .context("running cargo")?;
+    ensure!(
+        output.status.success(),
+        "build failed\n{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    Ok(())
+}
+
+/// Run `part` in a child process, so that it uses the freshly built source.
+fn run_part(year: &Year, day: u8, part: Part) -> Result<Record> {
+    let output = cargo(year)
+        .args([
+            "run",
+            "--quiet",
+            "--package",
+            "aoc",
+            "--",
+            "--format",
+            "json",
+        ])
+        .arg(format!("{}/{}/{}", year.year, day, part.number()))
+        .output()
+        .context("running cargo")?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    match stdout.lines().collect::<Vec<_>>()[..] {
+        [record] => Record::parse(record),
+        _ => bail!("no result was printed\n{}", stdout.trim_end()),
+    }
+}
+
+/// Print the answer to `part`, and what it used to be if it's changed.
+fn show(part: Part, answer: &str, previous: Option<&str>, duration: Duration) {
+    let changed = previous.filter(|&previous| previous != answer);
+    if answer.contains('\n') {
+        println!("part {} ({:.2?}):\n{}", part.number(), duration, answer);
+        if let Some(previous) = changed {
+            println!("was:\n{}", previous);
+        }
+    } else {
+        let was = changed.map_or(String::new(), |previous| format!(" (was {})", previous));
+        println!(
+            "part {}: {}{} ({:.2?})",
+            part.number(),
+            answer,
+            was,
+            duration
+        );
+    }
+}
+
+#[test]
+fn test_touches() {
+    use notify::event::{AccessKind, ModifyKind};
+
+    let source = Path::new("/aoc/src/day11.rs");
+    let input = Path::new("/aoc/input/11");
+    let event = |kind, path: &str| Event::new(kind).add_path(path.into());
+    let modify = EventKind::Modify(ModifyKind::Any);
+    assert!(touches(&event(modify, "/aoc/input/11"), &[source, input]));
+    assert!(!touches(&event(modify, "/aoc/input/1"), &[source, input]));
+    assert!(!touches(
+        &event(EventKind::Access(AccessKind::Any), "/aoc/src/day11.rs"),
+        &[source, input]
+    ));
+}
@@ -1,30 +1,47 @@
-use nom::{bytes::complete::tag, character::complete::alpha1, IResult};
-use petgraph::graph::{DefaultIx, NodeIndex, UnGraph as Graph};
+use aoc_core::{Day, Example};
+use aoc_utils::parse::{parse_lines, tag, IResult};
+use nom::{character::complete::alpha1, error::context};
+use petgraph::graph::UnGraph as Graph;
 use qu::ick_use::*;
 use std::{collections::HashMap, fmt};
 
-const INPUT: &str = include_str!("../input/12");
+pub struct Solution;
 
-pub fn first() -> Result<u32> {
-    let input = Input::parse()?;
-    todo!()
-}
+impl Day for Solution {
+    type Input<'a> = Input<'a>;
+    type Answer1 = u32;
+    type Answer2 = u32;
+
+    const EXAMPLES: &'static [Example] = &[Example {
+        input: EXAMPLE,
+        part1: Some("10"),
+        part2: Some("36"),
+    }];
+
+    fn parse(input: &str) -> Result<Input<'_>> {
+        Input::parse(input)
+    }
 
-pub fn second() -> Result<u32> {
-    let input = Input::parse()?;
-    todo!()
+    fn part1(_input: &Input) -> Result<u32> {
+        todo!()
+    }
+
+    fn part2(_input: &Input) -> Result<u32> {
+        todo!()
+    }
 }
 
-struct Input {
-    graph: Graph<Cave<'static>, ()>,
+#[allow(dead_code)]
+pub struct Input<'input> {
+    graph: Graph<Cave<'input>, ()>,
 }
 
-impl Input {
-    fn parse() -> Result<Self> {
+#[allow(dead_code)]
+impl<'input> Input<'input> {
+    fn parse(input: &'input str) -> Result<Self> {
         let mut graph = Graph::new_undirected();
         let mut caves = HashMap::new();
-        for line in INPUT.lines() {
-            let (_, (cave1, cave2)) = parse_line(line).unwrap();
+        for (cave1, cave2) in parse_lines(input, parse_line)? {
             let cave1 = *caves.entry(cave1).or_insert_with(|| graph.add_node(cave1));
             let cave2 = *caves.entry(cave2).or_insert_with(|| graph.add_node(cave2));
             graph.add_edge(cave1, cave2, ());
@@ -40,7 +57,7 @@ impl Input {
     }
 }
 
-fn parse_line(i: &str) -> IResult<&str, (Cave<'_>, Cave<'_>)> {
+fn parse_line(i: &str) -> IResult<'_, (Cave<'_>, Cave<'_>)> {
     let (i, first) = Cave::parse(i)?;
     let (i, _) = tag("-")(i)?;
     let (i, second) = Cave::parse(i)?;
@@ -55,8 +72,8 @@ enum Cave<'input> {
 }
 
 impl<'input> Cave<'input> {
-    fn parse(i: &'input str) -> IResult<&'input str, Self> {
-        let (i, name) = alpha1(i)?;
+    fn parse(i: &'input str) -> IResult<'input, Self> {
+        let (i, name) = context("a cave name", alpha1)(i)?;
         Ok((i, Self::from_str(name)))
     }
 
@@ -68,6 +85,7 @@ impl<'input> Cave<'input> {
         }
     }
 
+    #[allow(dead_code)]
     fn is_large(&self) -> bool {
         match self {
             Self::Named(name) => name.chars().next().unwrap().is_uppercase(),
@@ -85,3 +103,12 @@ impl fmt::Debug for Cave<'_> {
         }
     }
 }
+
+const EXAMPLE: &str = "start-A
+start-b
+A-c
+A-b
+b-d
+A-end
+b-end
+";
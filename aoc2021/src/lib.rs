@@ -0,0 +1,6 @@
+mod day12;
+
+aoc_core::days! {
+    2021;
+    12 => day12,
+}
@@ -0,0 +1,24 @@
+//! Seed each target's corpus with the day's examples and real input, so the fuzzer starts from
+//! inputs that get all the way through parsing.
+//!
+//! Run with `cargo run --example seed` from this directory.
+use std::{fs, io, path::Path};
+
+fn main() -> io::Result<()> {
+    let year = &aoc2022::YEAR;
+    for entry in year.days {
+        let corpus = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("corpus")
+            .join(format!("day{}", entry.day));
+        fs::create_dir_all(&corpus)?;
+        for (idx, example) in entry.solution.examples().iter().enumerate() {
+            fs::write(corpus.join(format!("example{}", idx + 1)), example.input)?;
+        }
+        let input = year.input_dir().join(entry.day.to_string());
+        if input.exists() {
+            fs::copy(&input, corpus.join("input"))?;
+        }
+        println!("seeded {}", corpus.display());
+    }
+    Ok(())
+}
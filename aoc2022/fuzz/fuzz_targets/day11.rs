@@ -0,0 +1,4 @@
+#![no_main]
+
+// the solutions are still `todo!()`, so only the parser can be fuzzed
+libfuzzer_sys::fuzz_target!(|data: &[u8]| aoc2022_fuzz::parse(11, data));
@@ -0,0 +1,7 @@
+#![no_main]
+
+/// The most steps the head may take in total. Real inputs take a few thousand, and the tail's
+/// visited points are all remembered, so far more just make runs time out.
+const MAX_STEPS: u64 = 1_000_000;
+
+libfuzzer_sys::fuzz_target!(|data: &[u8]| aoc2022_fuzz::run_bounded(9, data, MAX_STEPS));
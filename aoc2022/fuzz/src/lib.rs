@@ -0,0 +1,44 @@
+//! The harness shared by the fuzz targets, which feed arbitrary input through each day.
+//!
+//! Bad input should always give an error, so any panic is a bug.
+use aoc_core::{Entry, Part};
+use std::str;
+
+/// Parse `data` as input to `day` and solve both parts with it.
+pub fn run(day: u8, data: &[u8]) {
+    if let Ok(input) = str::from_utf8(data) {
+        for part in [Part::One, Part::Two] {
+            let _ = entry(day).solution.run(input, part);
+        }
+    }
+}
+
+/// Like [`run`], but skip inputs whose numbers add up to more than `max`, for days that take time
+/// in proportion to the numbers rather than the length of the input.
+pub fn run_bounded(day: u8, data: &[u8], max: u64) {
+    let total = data
+        .split(|b| !b.is_ascii_digit())
+        .filter(|digits| !digits.is_empty())
+        .map(|digits| {
+            digits.iter().fold(0u64, |n, d| {
+                n.saturating_mul(10).saturating_add(u64::from(d - b'0'))
+            })
+        })
+        .fold(0u64, u64::saturating_add);
+    if total <= max {
+        run(day, data);
+    }
+}
+
+/// Only parse `data` as input to `day`.
+pub fn parse(day: u8, data: &[u8]) {
+    if let Ok(input) = str::from_utf8(data) {
+        let _ = entry(day).solution.check_input(input);
+    }
+}
+
+fn entry(day: u8) -> &'static Entry {
+    aoc2022::YEAR
+        .find(day)
+        .expect("fuzzing a day that doesn't exist")
+}
@@ -1,19 +1,39 @@
+//! https://adventofcode.com/{{year}}/day/{{day}}
+use aoc_core::{Day, Example};
 use qu::ick_use::*;
 
-const INPUT: &str = include_str!("../input/12");
+pub struct Solution;
 
-pub fn first() -> Result<u32> {
-    todo!()
-}
+impl Day for Solution {
+    type Input<'a> = Input;
+    type Answer1 = u32;
+    type Answer2 = u32;
+
+    const EXAMPLES: &'static [Example] = &[Example {
+        input: EXAMPLE,
+        part1: None,
+        part2: None,
+    }];
+
+    fn parse(input: &str) -> Result<Input> {
+        Input::parse(input)
+    }
+
+    fn part1(_input: &Input) -> Result<u32> {
+        todo!()
+    }
 
-pub fn second() -> Result<u32> {
-    todo!()
+    fn part2(_input: &Input) -> Result<u32> {
+        todo!()
+    }
 }
 
-struct Input;
+pub struct Input;
 
 impl Input {
-    fn parse(i: &str) -> Result<Self> {
+    fn parse(_i: &str) -> Result<Self> {
         todo!()
     }
 }
+
+const EXAMPLE: &str = "";
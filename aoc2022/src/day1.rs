@@ -1,49 +1,362 @@
+use aoc_core::{Day, Example};
+use aoc_utils::parse::{number, parse_line};
 use qu::ick_use::*;
+use std::{
+    any::type_name,
+    cmp::Reverse,
+    collections::{BTreeMap, BinaryHeap},
+    fmt::{self, Write},
+    ops::{Div, Mul, RangeInclusive, Sub},
+    str::FromStr,
+};
 
-const INPUT: &str = include_str!("../input/1");
+pub struct Solution;
 
-pub fn first() -> Result<u32> {
-    let mut max = 0;
-    let mut total = 0;
-    for line in INPUT.lines() {
-        if line.is_empty() {
-            if total > max {
-                max = total;
-            }
-            total = 0;
-            continue;
-        }
-        let calories = line.parse::<u32>()?;
-        total += calories;
+impl Day for Solution {
+    type Input<'a> = &'a str;
+    type Answer1 = Total;
+    type Answer2 = Total;
+
+    const EXAMPLES: &'static [Example] = &[Example {
+        input: EXAMPLE,
+        part1: Some("24000"),
+        part2: Some("45000"),
+    }];
+
+    fn parse(input: &str) -> Result<&str> {
+        Ok(input)
     }
-    if total > max {
-        max = total;
+
+    fn part1(input: &&str) -> Result<Total> {
+        first(input)
+    }
+
+    fn part2(input: &&str) -> Result<Total> {
+        second(input)
+    }
+
+    fn analyse(input: &&str) -> Option<Result<String>> {
+        Some(stats_report::<Total>(input))
     }
-    Ok(max)
 }
 
-pub fn second() -> Result<u32> {
-    let mut elves = parse(INPUT).map(sum).collect::<Result<Vec<_>>>()?;
-    elves.sort();
-    if elves.len() < 3 {
+/// What the parts count calories in: `u64`, or `u128` with the `wide-calories` feature, for
+/// generated stress inputs too big for `u64`.
+#[cfg(not(feature = "wide-calories"))]
+type Total = u64;
+#[cfg(feature = "wide-calories")]
+type Total = u128;
+
+/// An unsigned integer type to count calories in.
+pub trait Calories:
+    Copy
+    + Ord
+    + fmt::Debug
+    + From<u16>
+    + FromStr
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+{
+    fn checked_add(self, other: Self) -> Option<Self>;
+    fn saturating_add(self, other: Self) -> Self;
+}
+
+macro_rules! calories {
+    ($($ty:ty),*) => {
+        $(impl Calories for $ty {
+            fn checked_add(self, other: Self) -> Option<Self> {
+                <$ty>::checked_add(self, other)
+            }
+
+            fn saturating_add(self, other: Self) -> Self {
+                <$ty>::saturating_add(self, other)
+            }
+        })*
+    };
+}
+
+calories!(u32, u64, u128);
+
+/// Width of the histogram buckets.
+const BUCKET_WIDTH: u16 = 1000;
+
+fn first<C: Calories>(input: &str) -> Result<C> {
+    let stats = ElfStats::from_blocks(parse(input), 1, BUCKET_WIDTH.into())?;
+    let top = stats.top();
+    ensure!(!top.is_empty(), "no elves");
+    Ok(top[0].calories)
+}
+
+fn second<C: Calories>(input: &str) -> Result<C> {
+    let stats = ElfStats::from_blocks(parse(input), 3, BUCKET_WIDTH.into())?;
+    let top = stats.top();
+    if top.len() < 3 {
         bail!("not enough elves");
     }
-    let len = elves.len();
-    Ok(elves[len - 1] + elves[len - 2] + elves[len - 3])
+    top.iter()
+        .try_fold(C::from(0), |acc, elf| add(acc, elf.calories))
+        .context("adding up the top three elves")
 }
 
-fn sum(input: impl Iterator<Item = Result<u32>>) -> Result<u32> {
-    let mut sum = 0;
+/// How the calories are spread between the elves: the percentiles, who's carrying the most, and a
+/// histogram.
+fn stats_report<C: Calories>(input: &str) -> Result<String> {
+    let stats = ElfStats::<C>::from_blocks(parse(input), 3, BUCKET_WIDTH.into())?;
+    let (Some(median), Some(p90)) = (stats.percentile(50.0), stats.percentile(90.0)) else {
+        bail!("no elves");
+    };
+    let mut report = String::new();
+    writeln!(
+        report,
+        "{} elves, median in {:?}, 90th percentile in {:?}",
+        stats.elves(),
+        median,
+        p90
+    )?;
+    let top = stats
+        .top()
+        .iter()
+        .map(|elf| format!("elf {} ({:?})", elf.index, elf.calories))
+        .collect::<Vec<_>>();
+    writeln!(report, "top {}: {}", top.len(), top.join(", "))?;
+    for (range, count) in stats.histogram() {
+        let range = format!("{:?}", range);
+        writeln!(report, "{:>13} {}", range, "#".repeat(count))?;
+    }
+    Ok(report)
+}
+
+fn sum<C: Calories>(input: impl Iterator<Item = Result<C>>) -> Result<C> {
+    let mut sum = C::from(0);
     for i in input {
-        sum += i?;
+        sum = add(sum, i?)?;
     }
     Ok(sum)
 }
 
-fn parse(input: &str) -> impl Iterator<Item = impl Iterator<Item = Result<u32>> + '_> + '_ {
-    input.split("\n\n").map(|block| {
+fn add<C: Calories>(a: C, b: C) -> Result<C> {
+    a.checked_add(b)
+        .with_context(|| format!("calories don't fit in {}", type_name::<C>()))
+}
+
+/// An elf and the calories they're carrying.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Elf<C> {
+    /// 1-based position of the elf in the input.
+    pub index: usize,
+    pub calories: C,
+}
+
+/// Statistics about the calories each elf carries, gathered in one pass without keeping every
+/// elf's total.
+#[derive(Debug, Clone)]
+pub struct ElfStats<C> {
+    /// How many of the top elves to keep.
+    n: usize,
+    /// The top `n` elves so far, as a min-heap of `(calories, Reverse(index))` so the one to drop
+    /// is on top. Among elves carrying the same amount, the one earlier in the input wins.
+    top: BinaryHeap<Reverse<(C, Reverse<usize>)>>,
+    elves: usize,
+    bucket_width: C,
+    /// Number of elves in each histogram bucket, keyed by the bucket's index.
+    histogram: BTreeMap<C, usize>,
+}
+
+impl<C: Calories> ElfStats<C> {
+    /// Keep the top `n` elves, and count totals in histogram buckets `bucket_width` wide.
+    pub fn new(n: usize, bucket_width: C) -> Self {
+        assert!(
+            bucket_width > C::from(0),
+            "histogram buckets must have a width"
+        );
+        ElfStats {
+            n,
+            top: BinaryHeap::with_capacity(n + 1),
+            elves: 0,
+            bucket_width,
+            histogram: BTreeMap::new(),
+        }
+    }
+
+    /// Gather stats from the blocks of calories returned by [`parse`].
+    pub fn from_blocks(
+        blocks: impl Iterator<Item = impl Iterator<Item = Result<C>>>,
+        n: usize,
+        bucket_width: C,
+    ) -> Result<Self> {
+        let mut stats = Self::new(n, bucket_width);
+        for block in blocks {
+            let total = sum(block).with_context(|| format!("elf {}", stats.elves + 1))?;
+            stats.push(total);
+        }
+        Ok(stats)
+    }
+
+    /// Add the next elf, who's carrying `calories` in total.
+    pub fn push(&mut self, calories: C) {
+        self.elves += 1;
+        *self
+            .histogram
+            .entry(calories / self.bucket_width)
+            .or_default() += 1;
+        self.top.push(Reverse((calories, Reverse(self.elves))));
+        if self.top.len() > self.n {
+            self.top.pop();
+        }
+    }
+
+    /// How many elves we've seen.
+    pub fn elves(&self) -> usize {
+        self.elves
+    }
+
+    /// The top `n` elves (or all of them, if there are fewer), most calories first.
+    pub fn top(&self) -> Vec<Elf<C>> {
+        // sorted by `Reverse`, so this is largest first
+        self.top
+            .clone()
+            .into_sorted_vec()
+            .into_iter()
+            .map(|Reverse((calories, Reverse(index)))| Elf { index, calories })
+            .collect()
+    }
+
+    /// The range of totals in each non-empty histogram bucket, with the number of elves in it.
+    pub fn histogram(&self) -> impl Iterator<Item = (RangeInclusive<C>, usize)> + '_ {
+        self.histogram
+            .iter()
+            .map(|(&bucket, &count)| (self.bucket(bucket), count))
+    }
+
+    /// The bucket holding the `p`th percentile total (by nearest rank), or `None` if there are no
+    /// elves. This is exact when the buckets are 1 wide.
+    pub fn percentile(&self, p: f64) -> Option<RangeInclusive<C>> {
+        assert!(
+            (0.0..=100.0).contains(&p),
+            "percentiles are between 0 and 100"
+        );
+        let rank = ((p / 100.0 * self.elves as f64).ceil() as usize).max(1);
+        let mut seen = 0;
+        for (&bucket, &count) in &self.histogram {
+            seen += count;
+            if seen >= rank {
+                return Some(self.bucket(bucket));
+            }
+        }
+        None
+    }
+
+    fn bucket(&self, bucket: C) -> RangeInclusive<C> {
+        let start = bucket * self.bucket_width;
+        start..=start.saturating_add(self.bucket_width - C::from(1))
+    }
+}
+
+fn parse<C: Calories>(
+    input: &str,
+) -> impl Iterator<Item = impl Iterator<Item = Result<C>> + '_> + '_ {
+    input.split("\n\n").map(move |block| {
         block
             .lines()
-            .map(|line| line.parse::<u32>().map_err(Into::into))
+            .map(move |line| Ok(parse_line(input, line, number::<C>)?))
     })
 }
+
+const EXAMPLE: &str = "1000
+2000
+3000
+
+4000
+
+5000
+6000
+
+7000
+8000
+9000
+
+10000
+";
+
+#[test]
+fn test_parse_error() {
+    let err = first::<u64>("1\n\n2\n\n\n3\n").unwrap_err();
+    assert_eq!(
+        format!("{:#}", err).lines().next(),
+        Some("elf 3: expected a number at line 5, column 1")
+    );
+}
+
+#[test]
+fn test_wide_calories() {
+    let big = u64::MAX / 2 + 1;
+    let one_elf = format!("1\n\n{}\n{}\n\n2\n", big, big);
+    // every elf fits, but the top three together don't
+    let three_elves = format!("{}\n\n{}\n\n1\n", big, big);
+    let part1 = Solution::part1(&one_elf.as_str());
+    let part2 = Solution::part2(&three_elves.as_str());
+
+    #[cfg(not(feature = "wide-calories"))]
+    {
+        let err = part1.unwrap_err();
+        assert_eq!(format!("{:#}", err), "elf 2: calories don't fit in u64");
+        let err = part2.unwrap_err();
+        assert_eq!(
+            format!("{:#}", err),
+            "adding up the top three elves: calories don't fit in u64"
+        );
+    }
+    #[cfg(feature = "wide-calories")]
+    {
+        assert_eq!(part1.unwrap(), 2 * big as u128);
+        assert_eq!(part2.unwrap(), 2 * big as u128 + 1);
+    }
+}
+
+#[test]
+fn test_elf_stats() {
+    let elf = |index, calories| Elf { index, calories };
+    let stats = ElfStats::<u32>::from_blocks(parse(EXAMPLE), 3, 1000).unwrap();
+    assert_eq!(stats.elves(), 5);
+    assert_eq!(stats.top(), [elf(4, 24000), elf(3, 11000), elf(5, 10000)]);
+    assert_eq!(stats.percentile(50.0), Some(10000..=10999));
+    assert_eq!(stats.percentile(0.0), Some(4000..=4999));
+    assert_eq!(stats.percentile(100.0), Some(24000..=24999));
+    assert_eq!(
+        stats.histogram().collect::<Vec<_>>(),
+        [
+            (4000..=4999, 1),
+            (6000..=6999, 1),
+            (10000..=10999, 1),
+            (11000..=11999, 1),
+            (24000..=24999, 1),
+        ]
+    );
+
+    // ties go to the earlier elf, and asking for more elves than there are gives all of them
+    let input = "5\n\n7\n\n5\n";
+    let stats = ElfStats::<u32>::from_blocks(parse(input), 2, 1).unwrap();
+    assert_eq!(stats.top(), [elf(2, 7), elf(1, 5)]);
+    let stats = ElfStats::<u32>::from_blocks(parse(input), 5, 1).unwrap();
+    assert_eq!(stats.top(), [elf(2, 7), elf(1, 5), elf(3, 5)]);
+    assert_eq!(stats.percentile(50.0), Some(5..=5));
+    assert_eq!(ElfStats::<u32>::new(3, 1).percentile(50.0), None);
+}
+
+#[test]
+fn test_stats_report() {
+    let report = Solution::analyse(&EXAMPLE).unwrap().unwrap();
+    assert_eq!(
+        report,
+        "5 elves, median in 10000..=10999, 90th percentile in 24000..=24999
+top 3: elf 4 (24000), elf 3 (11000), elf 5 (10000)
+  4000..=4999 #
+  6000..=6999 #
+10000..=10999 #
+11000..=11999 #
+24000..=24999 #
+"
+    );
+    assert!(Solution::analyse(&"1\nx\n").unwrap().is_err());
+}
@@ -1,29 +1,42 @@
+use aoc_core::{Answer, Day, Example, Rendered};
+use aoc_utils::parse::{parse_lines, signed, tag, IResult};
 use nom::{
     branch::alt,
-    bytes::complete::tag,
-    character::complete::digit1,
-    combinator::{all_consuming, map, map_res, opt, recognize, value},
+    combinator::{map, value},
     sequence::tuple,
-    IResult,
 };
 use qu::ick_use::*;
 use std::fmt;
 
-const INPUT: &str = include_str!("../input/10");
+pub struct Solution;
 
-pub fn first() -> Result<i32> {
-    let inst_iter = INPUT.lines().map(|line| Inst::parse(line).unwrap());
-    let signal_strength = Cpu::sum_cycles_20_60_100_140_180_220(inst_iter);
-    Ok(signal_strength)
-}
+impl Day for Solution {
+    type Input<'a> = Vec<Inst>;
+    type Answer1 = i64;
+    type Answer2 = Screen;
+
+    const EXAMPLES: &'static [Example] = &[Example {
+        input: EXAMPLE,
+        part1: Some("13140"),
+        part2: Some(EXAMPLE_SCREEN),
+    }];
+
+    fn parse(input: &str) -> Result<Vec<Inst>> {
+        Inst::parse(input)
+    }
 
-pub fn second() -> Result<Screen> {
-    let mut inst_iter = INPUT.lines().map(|line| Inst::parse(line).unwrap());
-    let mut mach = Machine::new();
-    for _ in 0..240 {
-        mach.cycle(&mut inst_iter);
+    fn part1(input: &Vec<Inst>) -> Result<i64> {
+        Cpu::sum_cycles_20_60_100_140_180_220(input.iter().copied())
+    }
+
+    fn part2(input: &Vec<Inst>) -> Result<Screen> {
+        let mut inst_iter = input.iter().copied();
+        let mut mach = Machine::new();
+        for _ in 0..240 {
+            mach.cycle(&mut inst_iter)?;
+        }
+        Ok(mach.screen)
     }
-    Ok(mach.screen)
 }
 
 struct Machine {
@@ -39,10 +52,10 @@ impl Machine {
         }
     }
 
-    fn cycle(&mut self, inst: &mut impl Iterator<Item = Inst>) {
+    fn cycle(&mut self, inst: &mut impl Iterator<Item = Inst>) -> Result {
         let x = self.cpu.cycles % 40;
         let y = self.cpu.cycles / 40;
-        if (self.cpu.regx - i32::try_from(x).unwrap()).abs() <= 1 {
+        if (self.cpu.regx - x as i64).abs() <= 1 {
             self.screen.set_pixel(x, y, true);
         }
         self.cpu.cycle(inst)
@@ -98,10 +111,25 @@ impl fmt::Display for Screen {
     }
 }
 
+impl Answer for Screen {
+    fn rendered(&self) -> Option<Rendered> {
+        let rows = (0..6)
+            .map(|y| {
+                (0..40)
+                    .map(|x| if self.pixel(x, y) { '#' } else { '.' })
+                    .collect()
+            })
+            .collect();
+        Some(Rendered { rows })
+    }
+}
+
 #[derive(Debug)]
 struct Cpu {
     cycles: usize,
-    regx: i32,
+    /// Wider than the values added to it, so that even 240 cycles of adding `i32::MAX` can't
+    /// overflow.
+    regx: i64,
     /// If an addx instruction is on its second cycle, this will be `Some`.
     in_flight: Option<i32>,
 }
@@ -115,86 +143,100 @@ impl Cpu {
         }
     }
 
-    fn cycle(&mut self, inst_iter: &mut impl Iterator<Item = Inst>) {
+    fn cycle(&mut self, inst_iter: &mut impl Iterator<Item = Inst>) -> Result {
         self.cycles += 1;
         if let Some(val) = self.in_flight.take() {
-            self.regx += val;
-            return;
+            self.regx += i64::from(val);
+            return Ok(());
         }
-        match inst_iter.next().unwrap() {
+        match inst_iter
+            .next()
+            .with_context(|| format!("program ended before cycle {}", self.cycles))?
+        {
             Inst::NoOp => (),
             Inst::AddX(val) => self.in_flight = Some(val),
         }
+        Ok(())
     }
 
-    fn sum_cycles_20_60_100_140_180_220(mut inst: impl Iterator<Item = Inst>) -> i32 {
+    fn sum_cycles_20_60_100_140_180_220(mut inst: impl Iterator<Item = Inst>) -> Result<i64> {
         let mut mach = Self::new();
         let mut acc = 0;
         for _ in 0..19 {
-            mach.cycle(&mut inst);
+            mach.cycle(&mut inst)?;
         }
         acc += mach.regx * 20;
         for _ in 0..40 {
-            mach.cycle(&mut inst);
+            mach.cycle(&mut inst)?;
         }
         acc += mach.regx * 60;
         for _ in 0..40 {
-            mach.cycle(&mut inst);
+            mach.cycle(&mut inst)?;
         }
         acc += mach.regx * 100;
         for _ in 0..40 {
-            mach.cycle(&mut inst);
+            mach.cycle(&mut inst)?;
         }
         acc += mach.regx * 140;
         for _ in 0..40 {
-            mach.cycle(&mut inst);
+            mach.cycle(&mut inst)?;
         }
         acc += mach.regx * 180;
         for _ in 0..40 {
-            mach.cycle(&mut inst);
+            mach.cycle(&mut inst)?;
         }
         acc += mach.regx * 220;
-        acc
+        Ok(acc)
     }
 }
 
-#[derive(Debug, Copy, Clone)]
-enum Inst {
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Inst {
     NoOp,
     AddX(i32),
 }
 
 impl Inst {
-    fn parse(i: &str) -> Result<Self> {
-        let (_, inst) = line(i).map_err(|_| format_err!("failed to parse line"))?;
-        Ok(inst)
+    fn parse(i: &str) -> Result<Vec<Self>> {
+        Ok(parse_lines(i, line)?)
     }
 }
 
-fn line(i: &str) -> IResult<&str, Inst> {
-    all_consuming(alt((
+impl fmt::Display for Inst {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Inst::NoOp => f.write_str("noop"),
+            Inst::AddX(n) => write!(f, "addx {}", n),
+        }
+    }
+}
+
+fn line(i: &str) -> IResult<'_, Inst> {
+    alt((
         value(Inst::NoOp, tag("noop")),
-        map(tuple((tag("addx "), number)), |(_, n)| Inst::AddX(n)),
-    )))(i)
+        map(tuple((tag("addx "), signed)), |(_, n)| Inst::AddX(n)),
+    ))(i)
 }
 
-fn number(i: &str) -> IResult<&str, i32> {
-    map_res(
-        recognize(tuple((opt(tag("-")), digit1))),
-        std::str::FromStr::from_str,
-    )(i)
+#[cfg(test)]
+crate::strategy::roundtrip! {
+    print: lines,
+    parse: Inst::parse,
+
+    fn strategy() -> impl Strategy<Value = Vec<Inst>> {
+        let inst = prop_oneof![Just(Inst::NoOp), any::<i32>().prop_map(Inst::AddX)];
+        vec(inst, 1..50)
+    }
 }
 
 #[test]
 fn test_with_example_input() {
-    let acc = Cpu::sum_cycles_20_60_100_140_180_220(
-        TEST_INPUT.lines().map(|line| Inst::parse(line).unwrap()),
-    );
+    let acc =
+        Cpu::sum_cycles_20_60_100_140_180_220(Inst::parse(EXAMPLE).unwrap().into_iter()).unwrap();
     assert_eq!(acc, 13140);
 }
 
-#[cfg(test)]
-const TEST_INPUT: &str = "addx 15
+const EXAMPLE: &str = "addx 15
 addx -11
 addx 6
 addx -3
@@ -340,3 +382,11 @@ addx -11
 noop
 noop
 noop";
+
+const EXAMPLE_SCREEN: &str = "##..##..##..##..##..##..##..##..##..##..
+###...###...###...###...###...###...###.
+####....####....####....####....####....
+#####.....#####.....#####.....#####.....
+######......######......######......####
+#######.......#######.......#######.....
+";
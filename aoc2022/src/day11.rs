@@ -1,31 +1,47 @@
+use aoc_core::{Day, Example};
+use aoc_utils::parse::{list, number, parse_all, tag, IResult};
 use nom::{
     branch::alt,
-    bytes::complete::tag,
-    character::complete::digit1,
-    combinator::{map_res, opt, value},
+    combinator::{map, opt, value},
     multi::many1,
     sequence::tuple,
-    IResult,
 };
 use qu::ick_use::*;
-use std::{num::ParseIntError, str::FromStr};
+use std::fmt;
 
-const INPUT: &str = include_str!("../input/11");
+pub struct Solution;
 
-pub fn first() -> Result<u32> {
-    let input = Input::parse(INPUT)?;
-    for monkey in input {
-        println!("{:#?}", monkey);
+impl Day for Solution {
+    type Input<'a> = Vec<Input>;
+    type Answer1 = u32;
+    type Answer2 = u32;
+
+    const EXAMPLES: &'static [Example] = &[Example {
+        input: EXAMPLE,
+        part1: Some("10605"),
+        part2: Some("2713310158"),
+    }];
+
+    fn parse(input: &str) -> Result<Vec<Input>> {
+        Input::parse(input)
+    }
+
+    fn part1(input: &Vec<Input>) -> Result<u32> {
+        for monkey in input {
+            event!(Level::DEBUG, "{:#?}", monkey);
+        }
+        todo!()
     }
-    todo!()
-}
 
-pub fn second() -> Result<u32> {
-    todo!()
+    fn part2(_input: &Vec<Input>) -> Result<u32> {
+        todo!()
+    }
 }
 
-#[derive(Debug)]
-struct Input {
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[allow(dead_code)]
+pub struct Input {
+    id: usize,
     starting_items: Vec<u32>,
     operation: Op,
     test_divisible_by: u32,
@@ -34,48 +50,87 @@ struct Input {
 }
 
 impl Input {
-    fn parse(mut i: &str) -> Result<Vec<Self>> {
-        let mut input = vec![];
-        while !i.is_empty() {
-            let monkey;
-            (i, monkey) =
-                parse_monkey(i).map_err(|e| format_err!("couldn't parse monkey: {}", e))?;
-            input.push(monkey);
-        }
-        Ok(input)
+    fn parse(i: &str) -> Result<Vec<Self>> {
+        Ok(parse_all(i, many1(parse_monkey))?)
     }
 }
 
-#[derive(Debug, Copy, Clone)]
+impl fmt::Display for Input {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let items = self
+            .starting_items
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>();
+        writeln!(f, "Monkey {}:", self.id)?;
+        writeln!(f, "  Starting items: {}", items.join(", "))?;
+        writeln!(f, "  Operation: new = old {}", self.operation)?;
+        writeln!(f, "  Test: divisible by {}", self.test_divisible_by)?;
+        writeln!(f, "    If true: throw to monkey {}", self.if_true_throw_to)?;
+        writeln!(
+            f,
+            "    If false: throw to monkey {}",
+            self.if_false_throw_to
+        )
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[allow(dead_code)]
 enum Op {
     Times(Operand),
     Plus(Operand),
 }
 
-#[derive(Debug, Copy, Clone)]
+impl fmt::Display for Op {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Op::Times(operand) => write!(f, "* {}", operand),
+            Op::Plus(operand) => write!(f, "+ {}", operand),
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[allow(dead_code)]
 enum Operand {
     Value(u32),
     Old,
 }
 
-fn parse_monkey(i: &str) -> IResult<&str, Input> {
-    let (i, _) = tuple((tag("Monkey "), digit1, tag(":\n  Starting items: ")))(i)?;
-    let (i, starting_items) = many1(map_res(tuple((digit1, opt(tag(", ")))), |(num, _)| {
-        <u32 as FromStr>::from_str(num)
-    }))(i)?;
+impl fmt::Display for Operand {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Operand::Value(value) => write!(f, "{}", value),
+            Operand::Old => f.write_str("old"),
+        }
+    }
+}
+
+/// Print the monkeys' notes, separated by blank lines.
+#[cfg(test)]
+fn print(monkeys: &[Input]) -> String {
+    let monkeys = monkeys.iter().map(ToString::to_string).collect::<Vec<_>>();
+    monkeys.join("\n")
+}
+
+fn parse_monkey(i: &str) -> IResult<'_, Input> {
+    let (i, (_, id, _)) = tuple((tag("Monkey "), number, tag(":\n  Starting items: ")))(i)?;
+    let (i, starting_items) = list(", ", number)(i)?;
     let (i, _) = tag("\n  Operation: new = old ")(i)?;
     let (i, operation) = parse_op(i)?;
     let (i, _) = tag("\n  Test: divisible by ")(i)?;
-    let (i, test_divisible_by) = map_res(digit1, FromStr::from_str)(i)?;
+    let (i, test_divisible_by) = number(i)?;
     let (i, _) = tag("\n    If true: throw to monkey ")(i)?;
-    let (i, if_true_throw_to) = map_res(digit1, FromStr::from_str)(i)?;
+    let (i, if_true_throw_to) = number(i)?;
     let (i, _) = tag("\n    If false: throw to monkey ")(i)?;
-    let (i, if_false_throw_to) = map_res(digit1, FromStr::from_str)(i)?;
+    let (i, if_false_throw_to) = number(i)?;
     let (i, _) = tag("\n")(i)?;
     let (i, _) = opt(tag("\n"))(i)?;
     Ok((
         i,
         Input {
+            id,
             starting_items,
             operation,
             test_divisible_by,
@@ -85,16 +140,10 @@ fn parse_monkey(i: &str) -> IResult<&str, Input> {
     ))
 }
 
-fn parse_op(i: &str) -> IResult<&str, Op> {
+fn parse_op(i: &str) -> IResult<'_, Op> {
     let (i, op) = alt((tag("*"), tag("+")))(i)?;
     let (i, _) = tag(" ")(i)?;
-    let (i, operand) = alt((
-        value(Operand::Old, tag("old")),
-        map_res(digit1, |val| {
-            let num = FromStr::from_str(val)?;
-            Ok::<Operand, ParseIntError>(Operand::Value(num))
-        }),
-    ))(i)?;
+    let (i, operand) = alt((value(Operand::Old, tag("old")), map(number, Operand::Value)))(i)?;
 
     let op = match op {
         "*" => Op::Times(operand),
@@ -104,3 +153,72 @@ fn parse_op(i: &str) -> IResult<&str, Op> {
 
     Ok((i, op))
 }
+
+#[cfg(test)]
+crate::strategy::roundtrip! {
+    print: print,
+    parse: Input::parse,
+
+    fn strategy() -> impl Strategy<Value = Vec<Input>> {
+        let operand = prop_oneof![Just(Operand::Old), any::<u32>().prop_map(Operand::Value)];
+        let operation = prop_oneof![
+            operand.clone().prop_map(Op::Times),
+            operand.prop_map(Op::Plus)
+        ];
+        let monkey = (
+            vec(any::<u32>(), 1..10),
+            operation,
+            1..100u32,
+            0..8usize,
+            0..8usize,
+        );
+        vec(monkey, 1..8).prop_map(|monkeys| {
+            monkeys
+                .into_iter()
+                .enumerate()
+                .map(|(id, (starting_items, operation, divisor, if_true, if_false))| Input {
+                    id,
+                    starting_items,
+                    operation,
+                    test_divisible_by: divisor,
+                    if_true_throw_to: if_true,
+                    if_false_throw_to: if_false,
+                })
+                .collect()
+        })
+    }
+}
+
+#[test]
+fn test_print() {
+    assert_eq!(print(&Input::parse(EXAMPLE).unwrap()), EXAMPLE);
+}
+
+const EXAMPLE: &str = "Monkey 0:
+  Starting items: 79, 98
+  Operation: new = old * 19
+  Test: divisible by 23
+    If true: throw to monkey 2
+    If false: throw to monkey 3
+
+Monkey 1:
+  Starting items: 54, 65, 75, 74
+  Operation: new = old + 6
+  Test: divisible by 19
+    If true: throw to monkey 2
+    If false: throw to monkey 0
+
+Monkey 2:
+  Starting items: 79, 60, 97
+  Operation: new = old * old
+  Test: divisible by 13
+    If true: throw to monkey 1
+    If false: throw to monkey 3
+
+Monkey 3:
+  Starting items: 74
+  Operation: new = old + 3
+  Test: divisible by 17
+    If true: throw to monkey 0
+    If false: throw to monkey 1
+";
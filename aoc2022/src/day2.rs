@@ -1,140 +1,598 @@
+use aoc_core::{Day, Example};
+use aoc_utils::parse::{parse_lines, tag, Error, Expected, IResult};
+use nom::sequence::separated_pair;
 use qu::ick_use::*;
+use std::{
+    collections::HashMap,
+    fmt::{self, Write},
+};
 use Res::*;
-use RPS::*;
 
-const INPUT: &str = include_str!("../input/2");
+pub struct Solution;
 
-pub fn first() -> Result<u32> {
-    let input = Round1::parse(INPUT)?;
-    Ok(input
-        .iter()
-        .map(|round| round.opponent.result(round.us).score() + round.us.score())
-        .sum())
+impl Day for Solution {
+    type Input<'a> = Input;
+    type Answer1 = u32;
+    type Answer2 = u32;
+
+    const EXAMPLES: &'static [Example] = &[Example {
+        input: EXAMPLE,
+        part1: Some("15"),
+        part2: Some("12"),
+    }];
+
+    fn parse(input: &str) -> Result<Input> {
+        let game = Game::new(RPS, RESULTS)?;
+        Ok(Input {
+            round1: Round1::parse(&game, input)?,
+            round2: Round2::parse(&game, input)?,
+            game,
+        })
+    }
+
+    fn part1(input: &Input) -> Result<u32> {
+        Ok(input
+            .round1
+            .iter()
+            .map(|round| input.game.score(round.opponent, round.us))
+            .sum())
+    }
+
+    fn part2(input: &Input) -> Result<u32> {
+        Ok(input
+            .round2
+            .iter()
+            .map(|round| {
+                let us = input.game.for_result(round.opponent, round.result);
+                input.game.score(round.opponent, us)
+            })
+            .sum())
+    }
+
+    fn analyse(input: &Input) -> Option<Result<String>> {
+        Some(readings_report(input))
+    }
 }
 
-pub fn second() -> Result<u32> {
-    let input = Round2::parse(INPUT)?;
-    Ok(input
-        .iter()
-        .map(|round| round.opponent.for_result(round.result).score() + round.result.score())
-        .sum())
+/// Rock, paper, scissors, in dominance order.
+const RPS: &[MoveRule] = &[
+    MoveRule {
+        name: "rock",
+        theirs: "A",
+        ours: "X",
+        score: 1,
+    },
+    MoveRule {
+        name: "scissors",
+        theirs: "C",
+        ours: "Z",
+        score: 3,
+    },
+    MoveRule {
+        name: "paper",
+        theirs: "B",
+        ours: "Y",
+        score: 2,
+    },
+];
+
+/// What the second column means when it's the result we want, and how each result scores.
+const RESULTS: [ResultRule; 3] = [
+    ResultRule {
+        result: Lose,
+        ours: "X",
+        score: 0,
+    },
+    ResultRule {
+        result: Draw,
+        ours: "Y",
+        score: 3,
+    },
+    ResultRule {
+        result: Win,
+        ours: "Z",
+        score: 6,
+    },
+];
+
+/// The strategy guide, read with both interpretations of the second column.
+pub struct Input {
+    game: Game,
+    round1: Vec<Round1>,
+    round2: Vec<Round2>,
 }
 
-#[derive(Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 struct Round1 {
-    opponent: RPS,
-    us: RPS,
+    opponent: Move,
+    us: Move,
 }
 
 impl Round1 {
-    fn parse(input: &str) -> Result<Vec<Self>> {
-        input
-            .lines()
-            .map(Round1::from_line)
-            .collect::<Option<Vec<_>>>()
-            .context("problem parsing input")
+    fn parse(game: &Game, input: &str) -> Result<Vec<Self>> {
+        Ok(parse_lines(input, |i| Round1::parse_line(game, i))?)
     }
 
-    fn from_line(input: &str) -> Option<Self> {
-        let input = input.trim();
-        let mut iter = input.chars();
-        let opponent = RPS::from_ch(iter.next()?)?;
-        iter.next()?;
-        let us = RPS::from_ch(iter.next()?)?;
-        Some(Self { opponent, us })
+    fn parse_line<'a>(game: &Game, i: &'a str) -> IResult<'a, Self> {
+        let (i, (opponent, us)) =
+            separated_pair(|i| game.parse_theirs(i), tag(" "), |i| game.parse_ours(i))(i)?;
+        Ok((i, Self { opponent, us }))
     }
 }
 
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 struct Round2 {
-    opponent: RPS,
+    opponent: Move,
     result: Res,
 }
 
 impl Round2 {
-    fn parse(input: &str) -> Result<Vec<Self>> {
-        input
-            .lines()
-            .map(Round2::from_line)
-            .collect::<Option<Vec<_>>>()
-            .context("problem parsing input")
-    }
-
-    fn from_line(input: &str) -> Option<Self> {
-        let input = input.trim();
-        let mut iter = input.chars();
-        let opponent = RPS::from_ch(iter.next()?)?;
-        iter.next()?;
-        let result = Res::from_char(iter.next()?)?;
-        Some(Self { opponent, result })
-    }
-}
-
-#[derive(Copy, Clone, PartialEq)]
-enum RPS {
-    Rock,
-    Paper,
-    Scissors,
-}
-
-impl RPS {
-    fn from_ch(ch: char) -> Option<Self> {
-        Some(match ch {
-            'A' | 'X' => Rock,
-            'B' | 'Y' => Paper,
-            'C' | 'Z' => Scissors,
-            _ => return None,
-        })
+    fn parse(game: &Game, input: &str) -> Result<Vec<Self>> {
+        Ok(parse_lines(input, |i| Round2::parse_line(game, i))?)
+    }
+
+    fn parse_line<'a>(game: &Game, i: &'a str) -> IResult<'a, Self> {
+        let (i, (opponent, result)) =
+            separated_pair(|i| game.parse_theirs(i), tag(" "), |i| game.parse_result(i))(i)?;
+        Ok((i, Self { opponent, result }))
+    }
+}
+
+/// One way to read the second column of the guide, giving each of its symbols a different meaning.
+/// The symbols are in the same order as the game's moves.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Reading {
+    Moves(Vec<Move>),
+    Results(Vec<Res>),
+}
+
+impl Reading {
+    /// Every reading of the second column: each way of matching its symbols to the moves and, when
+    /// there are three of them, to the results.
+    fn all(game: &Game) -> Vec<Self> {
+        let n = game.moves.len();
+        let mut readings = permutations(n)
+            .into_iter()
+            .map(Reading::Moves)
+            .collect::<Vec<_>>();
+        if n == 3 {
+            readings.extend(permutations(3).into_iter().map(|order| {
+                Reading::Results(order.into_iter().map(|i| [Lose, Draw, Win][i]).collect())
+            }));
+        }
+        readings
+    }
+
+    /// Our total score from following the guide, if this is what it meant.
+    fn score(&self, game: &Game, rounds: &[Round1]) -> u32 {
+        rounds
+            .iter()
+            .map(|round| {
+                let us = match self {
+                    Reading::Moves(moves) => moves[round.us],
+                    Reading::Results(results) => game.for_result(round.opponent, results[round.us]),
+                };
+                game.score(round.opponent, us)
+            })
+            .sum()
+    }
+
+    fn display<'a>(&'a self, game: &'a Game) -> impl fmt::Display + 'a {
+        struct Show<'a>(&'a Reading, &'a Game);
+
+        impl fmt::Display for Show<'_> {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                let Show(reading, game) = self;
+                // in the order the symbols sort, rather than the order the moves go round in
+                let mut symbols = (0..game.moves.len()).collect::<Vec<_>>();
+                symbols.sort_by_key(|&i| game.moves[i].ours);
+                for (n, i) in symbols.into_iter().enumerate() {
+                    if n > 0 {
+                        f.write_str(" ")?;
+                    }
+                    let symbol = game.moves[i].ours;
+                    match reading {
+                        Reading::Moves(moves) => {
+                            write!(f, "{}={}", symbol, game.moves[moves[i]].name)?
+                        }
+                        Reading::Results(results) => write!(f, "{}={}", symbol, results[i])?,
+                    }
+                }
+                Ok(())
+            }
+        }
+
+        Show(self, game)
+    }
+}
+
+/// The total score under every reading of the second column, with the best and worst readings.
+struct Analysis {
+    scores: Vec<(Reading, u32)>,
+    best: usize,
+    worst: usize,
+}
+
+impl Analysis {
+    fn new(game: &Game, rounds: &[Round1]) -> Self {
+        let scores = Reading::all(game)
+            .into_iter()
+            .map(|reading| {
+                let score = reading.score(game, rounds);
+                (reading, score)
+            })
+            .collect::<Vec<_>>();
+        // the first reading wins ties, in both directions
+        let best = (0..scores.len())
+            .rev()
+            .max_by_key(|&i| scores[i].1)
+            .unwrap_or(0);
+        let worst = (0..scores.len()).min_by_key(|&i| scores[i].1).unwrap_or(0);
+        Analysis {
+            scores,
+            best,
+            worst,
+        }
+    }
+
+    fn best(&self) -> &(Reading, u32) {
+        &self.scores[self.best]
+    }
+
+    fn worst(&self) -> &(Reading, u32) {
+        &self.scores[self.worst]
+    }
+}
+
+/// What we'd score from the strategy guide if it meant something else: the total under every
+/// reading of its second column, then the best and worst readings.
+fn readings_report(input: &Input) -> Result<String> {
+    let game = &input.game;
+    let analysis = Analysis::new(game, &input.round1);
+    let mut report = String::new();
+    for (reading, score) in &analysis.scores {
+        writeln!(report, "{:>6} {}", score, reading.display(game))?;
+    }
+    for (label, (reading, score)) in [("best", analysis.best()), ("worst", analysis.worst())] {
+        writeln!(report, "{}: {} ({})", label, reading.display(game), score)?;
+    }
+    Ok(report)
+}
+
+/// Every ordering of `0..n`, in lexicographic order.
+fn permutations(n: usize) -> Vec<Vec<usize>> {
+    if n == 0 {
+        return vec![vec![]];
+    }
+    let mut all = Vec::new();
+    for first in 0..n {
+        for rest in permutations(n - 1) {
+            let mut order = vec![first];
+            order.extend(rest.into_iter().map(|i| if i >= first { i + 1 } else { i }));
+            all.push(order);
+        }
     }
+    all
+}
+
+/// A move, as its position in the [`Game`]'s moves.
+type Move = usize;
+
+/// One of the moves in a [`Game`].
+#[derive(Debug, Copy, Clone)]
+struct MoveRule {
+    name: &'static str,
+    /// What the first column calls this move.
+    theirs: &'static str,
+    /// What the second column calls this move, when it's the move we should play.
+    ours: &'static str,
+    /// Points for playing this move.
+    score: u32,
+}
+
+/// One of the results in a [`Game`].
+#[derive(Debug, Copy, Clone)]
+struct ResultRule {
+    result: Res,
+    /// What the second column calls this result, when it's the result we want.
+    ours: &'static str,
+    /// Points for getting this result.
+    score: u32,
+}
+
+/// A game where the moves go round in a cycle and each one beats the next `N / 2` after it,
+/// wrapping round. With an odd number of moves, every pair of different moves has a winner: three
+/// gives rock, paper, scissors, and five gives rock, paper, scissors, lizard, Spock.
+#[derive(Debug, Clone)]
+struct Game {
+    /// The moves in dominance order.
+    moves: &'static [MoveRule],
+    /// Our score for each pair of moves, at `theirs * moves.len() + ours`.
+    scores: Vec<u32>,
+    /// What each column's symbols mean, for the parsers.
+    theirs: Vec<(&'static str, Move)>,
+    ours: Vec<(&'static str, Move)>,
+    wanted: Vec<(&'static str, Res)>,
+}
 
-    fn result(self, us: Self) -> Res {
-        match (self, us) {
-            (Rock, Paper) | (Paper, Scissors) | (Scissors, Rock) => Win,
-            (Rock, Rock) | (Paper, Paper) | (Scissors, Scissors) => Draw,
+impl Game {
+    fn new(moves: &'static [MoveRule], results: [ResultRule; 3]) -> Result<Self> {
+        ensure!(
+            moves.len() >= 3 && moves.len() % 2 == 1,
+            "a game needs an odd number of moves, at least 3, not {}",
+            moves.len()
+        );
+        for res in [Win, Lose, Draw] {
+            ensure!(
+                results.iter().filter(|rule| rule.result == res).count() == 1,
+                "{:?} must have exactly one rule",
+                res
+            );
+        }
+        let names = |symbol: fn(&MoveRule) -> &'static str| {
+            moves
+                .iter()
+                .map(move |rule| (symbol(rule), rule.name.to_owned()))
+        };
+        distinct("first", names(|rule| rule.theirs))?;
+        distinct("second", names(|rule| rule.ours))?;
+        distinct(
+            "second",
+            results
+                .iter()
+                .map(|rule| (rule.ours, rule.result.to_string())),
+        )?;
+        let mut game = Game {
+            moves,
+            scores: vec![],
+            theirs: moves.iter().map(|rule| rule.theirs).zip(0..).collect(),
+            ours: moves.iter().map(|rule| rule.ours).zip(0..).collect(),
+            wanted: results
+                .iter()
+                .map(|rule| (rule.ours, rule.result))
+                .collect(),
+        };
+        let n = moves.len();
+        game.scores = (0..n * n)
+            .map(|idx| {
+                let (theirs, ours) = (idx / n, idx % n);
+                let res = game.result(theirs, ours);
+                let rule = results.iter().find(|rule| rule.result == res);
+                moves[ours].score + rule.expect("every result has a rule").score
+            })
+            .collect();
+        Ok(game)
+    }
+
+    /// The result for us, if the opponent plays `theirs` and we play `ours`.
+    fn result(&self, theirs: Move, ours: Move) -> Res {
+        let n = self.moves.len();
+        match (theirs + n - ours) % n {
+            0 => Draw,
+            ahead if ahead <= n / 2 => Win,
             _ => Lose,
         }
     }
 
-    /// If `self` is our opponent's choice, and we want the result `res`, what should we
-    /// choose.
-    fn for_result(self, res: Res) -> Self {
-        match (self, res) {
-            (Rock, Win) | (Paper, Draw) | (Scissors, Lose) => Paper,
-            (Rock, Draw) | (Paper, Lose) | (Scissors, Win) => Rock,
-            (Rock, Lose) | (Paper, Win) | (Scissors, Draw) => Scissors,
+    /// If the opponent plays `theirs`, and we want the result `res`, what should we play.
+    fn for_result(&self, theirs: Move, res: Res) -> Move {
+        let n = self.moves.len();
+        match res {
+            // the move just before theirs beats it, and the one just after loses to it
+            Win => (theirs + n - 1) % n,
+            Draw => theirs,
+            Lose => (theirs + 1) % n,
+        }
+    }
+
+    /// Our score for the round where the opponent plays `theirs` and we play `ours`.
+    fn score(&self, theirs: Move, ours: Move) -> u32 {
+        self.scores[theirs * self.moves.len() + ours]
+    }
+
+    /// The opponent's move, in the first column.
+    fn parse_theirs<'a>(&self, i: &'a str) -> IResult<'a, Move> {
+        one_of(&self.theirs, i)
+    }
+
+    /// Our move, if the second column is what we should play.
+    fn parse_ours<'a>(&self, i: &'a str) -> IResult<'a, Move> {
+        one_of(&self.ours, i)
+    }
+
+    /// The result we want, if that's what the second column is.
+    fn parse_result<'a>(&self, i: &'a str) -> IResult<'a, Res> {
+        one_of(&self.wanted, i)
+    }
+}
+
+/// Check that each of `symbols` in `column` means only one thing, given as its name.
+fn distinct(column: &str, symbols: impl Iterator<Item = (&'static str, String)>) -> Result {
+    let mut seen = HashMap::new();
+    for (symbol, name) in symbols {
+        ensure!(!symbol.is_empty(), "{} has no symbol", name);
+        if let Some(other) = seen.insert(symbol, name.clone()) {
+            bail!(
+                "`{}` means both {} and {} in the {} column",
+                symbol,
+                other,
+                name,
+                column
+            );
         }
     }
+    Ok(())
+}
 
-    fn score(self) -> u32 {
-        match self {
-            Rock => 1,
-            Paper => 2,
-            Scissors => 3,
+/// Match any of `symbols`, giving the value that goes with it.
+fn one_of<'a, T: Copy>(symbols: &[(&'static str, T)], i: &'a str) -> IResult<'a, T> {
+    for &(symbol, value) in symbols {
+        if let Some(rest) = i.strip_prefix(symbol) {
+            return Ok((rest, value));
         }
     }
+    Err(nom::Err::Error(Error {
+        input: i,
+        expected: symbols
+            .iter()
+            .map(|&(symbol, _)| Expected::Token(symbol))
+            .collect(),
+    }))
 }
 
-#[derive(Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 enum Res {
     Win,
     Lose,
     Draw,
 }
 
-impl Res {
-    fn from_char(ch: char) -> Option<Self> {
-        Some(match ch {
-            'X' => Lose,
-            'Y' => Draw,
-            'Z' => Win,
-            _ => return None,
+impl fmt::Display for Res {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Win => "win",
+            Lose => "lose",
+            Draw => "draw",
         })
     }
+}
+
+#[cfg(test)]
+crate::strategy::roundtrip! {
+    print: |rounds: &Vec<(Round1, Round2)>| print(&rps(), rounds),
+    parse: |input: &str| -> Result<Vec<(Round1, Round2)>> {
+        let game = rps();
+        let round1 = Round1::parse(&game, input)?;
+        let round2 = Round2::parse(&game, input)?;
+        Ok(round1.into_iter().zip(round2).collect())
+    },
+
+    fn rps() -> Game {
+        Game::new(RPS, RESULTS).unwrap()
+    }
+
+    /// Print the guide, with the second column as both a move and a result.
+    fn print(game: &Game, rounds: &[(Round1, Round2)]) -> String {
+        rounds
+            .iter()
+            .map(|(round1, _)| {
+                let theirs = game.moves[round1.opponent].theirs;
+                format!("{} {}\n", theirs, game.moves[round1.us].ours)
+            })
+            .collect()
+    }
+
+    fn strategy() -> impl Strategy<Value = Vec<(Round1, Round2)>> {
+        let game = rps();
+        let round = (0..3usize, 0..3usize).prop_map(move |(opponent, us)| {
+            // both readings of the same symbol
+            let symbol = game.moves[us].ours;
+            let &(_, result) = game.wanted.iter().find(|&&(ours, _)| ours == symbol).unwrap();
+            let round2 = Round2 {
+                opponent,
+                result,
+            };
+            (Round1 { opponent, us }, round2)
+        });
+        vec(round, 1..50)
+    }
+}
 
-    fn score(self) -> u32 {
-        match self {
-            Win => 6,
-            Draw => 3,
-            Lose => 0,
+const EXAMPLE: &str = "A Y
+B X
+C Z
+";
+
+#[test]
+fn test_game() {
+    let rps = Game::new(RPS, RESULTS).unwrap();
+    let [rock, scissors, paper] = [0, 1, 2];
+    assert_eq!(rps.moves[rock].name, "rock");
+    assert_eq!(rps.result(rock, paper), Win);
+    assert_eq!(rps.result(paper, rock), Lose);
+    assert_eq!(rps.result(scissors, scissors), Draw);
+    assert_eq!(rps.for_result(scissors, Win), rock);
+    assert_eq!(rps.score(rock, paper), 8);
+
+    let symbols = ["1", "2", "3", "4", "5", "6", "7"];
+    let names = ["rock", "scissors", "lizard", "paper", "spock"];
+    let moves = |n: usize| -> &'static [MoveRule] {
+        Vec::leak(
+            (0..n)
+                .map(|i| MoveRule {
+                    name: names.get(i).copied().unwrap_or("other"),
+                    theirs: symbols[i],
+                    ours: symbols[i],
+                    score: i as u32 + 1,
+                })
+                .collect(),
+        )
+    };
+    let rpsls = Game::new(moves(5), RESULTS).unwrap();
+    let [rock, scissors, lizard, paper, spock] = [0, 1, 2, 3, 4];
+    for (winner, losers) in [
+        (rock, [scissors, lizard]),
+        (scissors, [lizard, paper]),
+        (lizard, [paper, spock]),
+        (paper, [spock, rock]),
+        (spock, [rock, scissors]),
+    ] {
+        for loser in losers {
+            assert_eq!(rpsls.result(loser, winner), Win);
+            assert_eq!(rpsls.result(winner, loser), Lose);
+        }
+    }
+
+    // in any game, every result can be reached from every move, and each move wins as often as it
+    // loses
+    let seven = Game::new(moves(7), RESULTS).unwrap();
+    for game in [&rps, &rpsls, &seven] {
+        let n = game.moves.len();
+        for theirs in 0..n {
+            for res in [Win, Lose, Draw] {
+                assert_eq!(game.result(theirs, game.for_result(theirs, res)), res);
+            }
+            let wins = (0..n).filter(|&ours| game.result(theirs, ours) == Win);
+            assert_eq!(wins.count(), n / 2);
         }
     }
+
+    assert!(Game::new(moves(4), RESULTS).is_err());
+    // a single move always draws, so it could never win or lose
+    assert!(Game::new(&RPS[..1], RESULTS).is_err());
+    let mut clash = RESULTS;
+    clash[2].ours = "Y";
+    let err = Game::new(RPS, clash).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "`Y` means both draw and win in the second column"
+    );
+    assert!(Game::new(RPS, [RESULTS[0]; 3]).is_err());
+}
+
+#[test]
+fn test_readings() {
+    let input = Solution::parse(EXAMPLE).unwrap();
+    let game = &input.game;
+    let analysis = Analysis::new(game, &input.round1);
+    assert_eq!(analysis.scores.len(), 12);
+    assert_eq!(permutations(3).len(), 6);
+
+    // the two readings the puzzle uses give the parts' answers
+    let [rock, scissors, paper] = [0, 1, 2];
+    let score = |reading: Reading| reading.score(game, &input.round1);
+    assert_eq!(score(Reading::Moves(vec![rock, scissors, paper])), 15);
+    assert_eq!(score(Reading::Results(vec![Lose, Win, Draw])), 12);
+
+    let (best, score) = analysis.best();
+    assert_eq!(*score, 24);
+    assert_eq!(best.display(game).to_string(), "X=scissors Y=paper Z=rock");
+    assert_eq!(analysis.worst().1, 6);
+    assert!(analysis.scores.iter().all(|&(_, s)| (6..=24).contains(&s)));
+
+    let report = Solution::analyse(&input).unwrap().unwrap();
+    assert_eq!(report.lines().count(), 14);
+    assert!(report.starts_with("    15 X=rock Y=paper Z=scissors\n"));
+    assert!(report
+        .ends_with("best: X=scissors Y=paper Z=rock (24)\nworst: X=rock Y=scissors Z=paper (6)\n"));
 }
@@ -1,92 +1,276 @@
+use aoc_core::{Day, Example};
+use aoc_utils::parse::{parse_lines, Error, Expected, IResult};
 use qu::ick_use::*;
+use std::fmt;
 
-const INPUT: &str = include_str!("../input/3");
+pub struct Solution;
 
-pub fn first() -> Result<u32> {
-    let input = Input::parse(INPUT)?;
-    Ok(input
-        .iter()
-        .map(|input| input.find_match_1().unwrap().score())
-        .sum::<u32>())
+impl Day for Solution {
+    type Input<'a> = Vec<Input>;
+    type Answer1 = u32;
+    type Answer2 = u32;
+
+    const EXAMPLES: &'static [Example] = &[Example {
+        input: EXAMPLE,
+        part1: Some("157"),
+        part2: Some("70"),
+    }];
+
+    fn parse(input: &str) -> Result<Vec<Input>> {
+        Input::parse(input)
+    }
+
+    fn part1(input: &Vec<Input>) -> Result<u32> {
+        input
+            .iter()
+            .enumerate()
+            .map(|(idx, input)| {
+                let item = input
+                    .find_match_1()
+                    .with_context(|| format!("rucksack {}", idx + 1))?;
+                Ok(item.priority())
+            })
+            .sum()
+    }
+
+    fn part2(input: &Vec<Input>) -> Result<u32> {
+        badges(input, GROUP_SIZE)
+    }
 }
 
-pub fn second() -> Result<u32> {
-    let input = Input::parse(INPUT)?;
-    Ok(input
-        .chunks(3)
-        .map(|chunk| team_supply(chunk).unwrap().score())
-        .sum::<u32>())
+/// How many elves there are in each group.
+const GROUP_SIZE: usize = 3;
+
+/// The sum of the priorities of each group's badge, splitting the elves into groups of `size`.
+fn badges(input: &[Input], size: usize) -> Result<u32> {
+    ensure!(size > 0, "groups need at least one elf");
+    input
+        .chunks(size)
+        .enumerate()
+        .map(|(idx, group)| {
+            ensure!(
+                group.len() == size,
+                "the last group only has {} elves",
+                group.len()
+            );
+            let item = team_supply(group).with_context(|| format!("group {}", idx + 1))?;
+            Ok(item.priority())
+        })
+        .sum()
 }
 
-fn team_supply(input: &[Input]) -> Option<Supplies> {
-    assert_eq!(input.len(), 3);
-    for s1 in input[0].supplies.iter() {
-        for s2 in input[1].supplies.iter() {
-            for s3 in input[2].supplies.iter() {
-                if s1 == s2 && s2 == s3 {
-                    return Some(*s1);
-                }
-            }
-        }
-    }
-    None
+/// The one item every elf in `group` is carrying.
+fn team_supply(group: &[Input]) -> Result<Item> {
+    group
+        .iter()
+        .fold(Supplies::ALL, |common, rucksack| common & rucksack.all())
+        .only()
+        .context("looking for the item common to the whole group")
 }
 
-struct Input {
-    supplies: Vec<Supplies>,
+#[derive(Debug, PartialEq, Eq)]
+pub struct Input {
+    /// Everything in the rucksack, in order and with repeats.
+    items: Vec<Item>,
+    /// The items in each half of the rucksack.
+    compartments: [Supplies; 2],
 }
 
 impl Input {
     fn parse(input: &str) -> Result<Vec<Self>> {
-        input
-            .lines()
-            .map(Input::from_line)
-            .collect::<Result<Vec<_>>>()
-    }
-
-    fn from_line(line: &str) -> Result<Self> {
-        let supplies = line
-            .trim()
-            .chars()
-            .map(Supplies::from_char)
-            .collect::<Option<Vec<_>>>()
-            .context("could not parse input")?;
-        Ok(Self { supplies })
-    }
-
-    fn find_match_1(&self) -> Option<Supplies> {
-        assert!(self.supplies.len() % 2 == 0);
-        let compartment_len = self.supplies.len() / 2;
-        // probably quicker to sort
-        for ch in self.supplies[..compartment_len].iter() {
-            for ch2 in self.supplies[compartment_len..].iter() {
-                if ch == ch2 {
-                    return Some(*ch);
+        Ok(parse_lines(input, Input::parse_line)?)
+    }
+
+    fn parse_line(i: &str) -> IResult<'_, Self> {
+        let mut items = Vec::with_capacity(i.len());
+        for (idx, ch) in i.char_indices() {
+            match Item::from_char(ch) {
+                Some(item) => items.push(item),
+                None => {
+                    let expected = Expected::Named("an item (a letter)");
+                    return Err(nom::Err::Error(Error::new(&i[idx..], expected)));
                 }
             }
         }
-        None
+        Ok(("", Self::new(items)))
+    }
+
+    fn new(items: Vec<Item>) -> Self {
+        let (first, second) = items.split_at(items.len() / 2);
+        Self {
+            compartments: [first.iter().collect(), second.iter().collect()],
+            items,
+        }
+    }
+
+    /// Everything in the rucksack.
+    fn all(&self) -> Supplies {
+        self.compartments[0] | self.compartments[1]
+    }
+
+    fn find_match_1(&self) -> Result<Item> {
+        ensure!(
+            self.items.len().is_multiple_of(2),
+            "a rucksack has an odd number of items"
+        );
+        (self.compartments[0] & self.compartments[1])
+            .only()
+            .context("looking for the item in both compartments")
     }
 }
 
-#[derive(Copy, Clone, PartialEq)]
-struct Supplies {
-    ch: char,
+impl fmt::Display for Input {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for item in &self.items {
+            write!(f, "{}", item)?;
+        }
+        Ok(())
+    }
 }
 
-impl Supplies {
+/// An item, as its priority from 1 to 52.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+struct Item(u8);
+
+impl Item {
     fn from_char(ch: char) -> Option<Self> {
         match ch {
-            'a'..='z' | 'A'..='Z' => Some(Self { ch }),
+            'a'..='z' => Some(Item(ch as u8 - b'a' + 1)),
+            'A'..='Z' => Some(Item(ch as u8 - b'A' + 27)),
             _ => None,
         }
     }
 
-    fn score(self) -> u32 {
-        match self.ch {
-            'a'..='z' => self.ch as u32 - 'a' as u32 + 1,
-            'A'..='Z' => self.ch as u32 - 'A' as u32 + 27,
-            _ => unreachable!(),
+    fn priority(self) -> u32 {
+        self.0.into()
+    }
+}
+
+impl fmt::Display for Item {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let ch = match self.0 {
+            1..=26 => b'a' + self.0 - 1,
+            _ => b'A' + self.0 - 27,
+        };
+        write!(f, "{}", ch as char)
+    }
+}
+
+/// A set of items, with bit `n` set if the item with priority `n` is in it.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+struct Supplies(u64);
+
+impl Supplies {
+    const ALL: Supplies = Supplies(((1 << 52) - 1) << 1);
+
+    fn items(self) -> impl Iterator<Item = Item> {
+        (1..=52).filter(move |n| self.0 & 1 << n != 0).map(Item)
+    }
+
+    /// The one item in the set, or an error saying there are none or several.
+    fn only(self) -> Result<Item> {
+        match self.0.count_ones() {
+            0 => bail!("no item is common to all of them"),
+            1 => Ok(Item(self.0.trailing_zeros() as u8)),
+            _ => {
+                let items = self
+                    .items()
+                    .map(|item| item.to_string())
+                    .collect::<Vec<_>>();
+                bail!(
+                    "several items are common to all of them: {}",
+                    items.join(", ")
+                )
+            }
         }
     }
 }
+
+impl<'a> FromIterator<&'a Item> for Supplies {
+    fn from_iter<I: IntoIterator<Item = &'a Item>>(iter: I) -> Self {
+        Supplies(iter.into_iter().fold(0, |set, item| set | 1 << item.0))
+    }
+}
+
+impl std::ops::BitAnd for Supplies {
+    type Output = Self;
+
+    fn bitand(self, other: Self) -> Self {
+        Supplies(self.0 & other.0)
+    }
+}
+
+impl std::ops::BitOr for Supplies {
+    type Output = Self;
+
+    fn bitor(self, other: Self) -> Self {
+        Supplies(self.0 | other.0)
+    }
+}
+
+#[cfg(test)]
+crate::strategy::roundtrip! {
+    print: lines,
+    parse: Input::parse,
+
+    fn strategy() -> impl Strategy<Value = Vec<Input>> {
+        // few enough kinds of item that most rucksacks repeat some
+        let item = prop_oneof![(1..=5u8).prop_map(Item), (1..=52u8).prop_map(Item)];
+        vec(vec(item, 0..60).prop_map(Input::new), 1..20)
+    }
+}
+
+const EXAMPLE: &str = "vJrwpWtwJgWrhcsFMMfFFhFp
+jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL
+PmmdzqPrVvPwwTWBwg
+wMqvLMZHhHMvwLHjbvcjnnSBnvTQFn
+ttgJtRGJQctTZtZT
+CrZsJsPPZsGzwwsLwLmpwMDw
+";
+
+#[test]
+fn test_groups() {
+    let input = Input::parse(EXAMPLE).unwrap();
+    assert_eq!(badges(&input, 3).unwrap(), 70);
+    // alone, an elf has as many items in common as they're carrying
+    assert!(badges(&input[..1], 1).is_err());
+
+    let input = Input::parse("abX\nXcd\nX\n").unwrap();
+    assert_eq!(badges(&input, 1).unwrap_err().to_string(), "group 1");
+    assert_eq!(badges(&input[2..], 1).unwrap(), 50);
+    assert_eq!(badges(&input[..2], 2).unwrap(), 50);
+    let err = badges(&input, 2).unwrap_err();
+    assert_eq!(format!("{:#}", err), "the last group only has 1 elves");
+    assert!(badges(&input, 0).is_err());
+
+    let input = Input::parse("abAB\nAbBa\nxyz\n").unwrap();
+    let err = badges(&input[..2], 2).unwrap_err();
+    assert_eq!(
+        format!("{:#}", err),
+        "group 1: looking for the item common to the whole group: \
+         several items are common to all of them: a, b, A, B"
+    );
+    let err = badges(&input[1..], 2).unwrap_err();
+    assert_eq!(
+        format!("{:#}", err),
+        "group 1: looking for the item common to the whole group: \
+         no item is common to all of them"
+    );
+    let err = input[0].find_match_1().unwrap_err();
+    assert_eq!(
+        format!("{:#}", err),
+        "looking for the item in both compartments: no item is common to all of them"
+    );
+    assert!(input[2].find_match_1().is_err());
+
+    let err = Input::parse("abAB\nAb-a\n").unwrap_err();
+    assert_eq!(
+        err.to_string().lines().next(),
+        Some("expected an item (a letter) at line 2, column 3")
+    );
+
+    // the items are kept as they were, repeats and all
+    let input = Input::parse("ZaaBab\n").unwrap();
+    assert_eq!(input[0].to_string(), "ZaaBab");
+    assert_eq!(input[0].find_match_1().unwrap(), Item(1));
+}
@@ -1,37 +1,43 @@
-use nom::{bytes::complete::tag, character::complete::digit1, Finish, IResult};
+use aoc_core::{Day, Example};
+use aoc_utils::parse::{number, parse_lines, tag, IResult};
 use qu::ick_use::*;
-use std::ops::RangeInclusive;
+use std::{fmt, ops::RangeInclusive};
 
-const INPUT: &str = include_str!("../input/4");
+pub struct Solution;
 
-pub fn first() -> Result<u32> {
-    let input = Input::parse(INPUT)?;
-    let count_contains = input.iter().filter(|i| i.fully_contains()).count();
-    Ok(count_contains.try_into().unwrap())
-}
+impl Day for Solution {
+    type Input<'a> = Vec<Input>;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    const EXAMPLES: &'static [Example] = &[Example {
+        input: EXAMPLE,
+        part1: Some("2"),
+        part2: Some("4"),
+    }];
+
+    fn parse(input: &str) -> Result<Vec<Input>> {
+        Input::parse(input)
+    }
+
+    fn part1(input: &Vec<Input>) -> Result<usize> {
+        Ok(input.iter().filter(|i| i.fully_contains()).count())
+    }
 
-pub fn second() -> Result<u32> {
-    let input = Input::parse(INPUT)?;
-    let count_overlap = input.iter().filter(|i| i.ranges_overlap()).count();
-    Ok(count_overlap.try_into().unwrap())
+    fn part2(input: &Vec<Input>) -> Result<usize> {
+        Ok(input.iter().filter(|i| i.ranges_overlap()).count())
+    }
 }
 
-struct Input {
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Input {
     first: RangeInclusive<u32>,
     second: RangeInclusive<u32>,
 }
 
 impl Input {
     fn parse(input: &str) -> Result<Vec<Input>> {
-        input
-            .lines()
-            .map(Input::parse_row)
-            .collect::<Result<Vec<_>>>()
-    }
-
-    fn parse_row(input: &str) -> Result<Input> {
-        let (_, input) = row(input).finish().unwrap();
-        Ok(input)
+        Ok(parse_lines(input, row)?)
     }
 
     /// One of the ranges fully contains the other
@@ -45,24 +51,54 @@ impl Input {
     }
 }
 
+impl fmt::Display for Input {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}-{},{}-{}",
+            self.first.start(),
+            self.first.end(),
+            self.second.start(),
+            self.second.end()
+        )
+    }
+}
+
 // parse
 
-fn row(i: &str) -> IResult<&str, Input> {
+fn row(i: &str) -> IResult<'_, Input> {
     let (i, first) = range(i)?;
     let (i, _) = tag(",")(i)?;
     let (i, second) = range(i)?;
     Ok((i, Input { first, second }))
 }
 
-fn range(i: &str) -> IResult<&str, RangeInclusive<u32>> {
+fn range(i: &str) -> IResult<'_, RangeInclusive<u32>> {
     let (i, start) = number(i)?;
     let (i, _) = tag("-")(i)?;
     let (i, end) = number(i)?;
     Ok((i, RangeInclusive::new(start, end)))
 }
 
-fn number(i: &str) -> IResult<&str, u32> {
-    let (i, num) = digit1(i)?;
-    let num = num.parse::<u32>().unwrap();
-    Ok((i, num))
+const EXAMPLE: &str = "2-4,6-8
+2-3,4-5
+5-7,7-9
+2-8,3-7
+6-6,4-6
+2-6,4-8
+";
+
+#[cfg(test)]
+crate::strategy::roundtrip! {
+    print: lines,
+    parse: Input::parse,
+
+    fn strategy() -> impl Strategy<Value = Vec<Input>> {
+        let range = (0..100u32, 0..100u32).prop_map(|(start, end)| start..=end);
+        vec((range.clone(), range), 1..20).prop_map(|rows| {
+            rows.into_iter()
+                .map(|(first, second)| Input { first, second })
+                .collect()
+        })
+    }
 }
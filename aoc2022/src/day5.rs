@@ -1,39 +1,50 @@
+use aoc_core::{Day, Example};
+use aoc_utils::parse::{number, parse_line, tag, IResult};
 use nom::{
     branch::alt,
-    bytes::complete::tag,
-    character::complete::{anychar, digit1},
-    combinator::{map, map_res, opt, value},
-    IResult,
+    character::complete::anychar,
+    combinator::{map, opt, value},
+    multi::many0,
 };
 use qu::ick_use::*;
-use std::str::FromStr;
+use std::fmt;
 
-const INPUT: &str = include_str!("../input/5");
+pub struct Solution;
 
-pub fn first() -> Result<String> {
-    let Input {
-        mut start,
-        instructions,
-    } = Input::parse(INPUT)?;
-    for inst in instructions {
-        start.apply_instruction(inst);
+impl Day for Solution {
+    type Input<'a> = Input;
+    type Answer1 = String;
+    type Answer2 = String;
+
+    const EXAMPLES: &'static [Example] = &[Example {
+        input: EXAMPLE,
+        part1: Some("CMZ"),
+        part2: Some("MCD"),
+    }];
+
+    fn parse(input: &str) -> Result<Input> {
+        Input::parse(input)
     }
-    Ok(start.top_crates())
-}
 
-pub fn second() -> Result<String> {
-    let Input {
-        mut start,
-        instructions,
-    } = Input::parse(INPUT)?;
-    for inst in instructions {
-        start.apply_instruction_9001(inst);
+    fn part1(input: &Input) -> Result<String> {
+        let mut positions = input.start.clone();
+        for inst in &input.instructions {
+            positions.apply_instruction(*inst)?;
+        }
+        positions.top_crates()
+    }
+
+    fn part2(input: &Input) -> Result<String> {
+        let mut positions = input.start.clone();
+        for inst in &input.instructions {
+            positions.apply_instruction_9001(*inst)?;
+        }
+        positions.top_crates()
     }
-    Ok(start.top_crates())
 }
 
-#[derive(Debug)]
-struct Input {
+#[derive(Debug, PartialEq, Eq)]
+pub struct Input {
     start: CratePositions,
     instructions: Vec<Instruction>,
 }
@@ -42,19 +53,29 @@ impl Input {
     fn parse(i: &str) -> Result<Self> {
         let mut lines = i.lines().peekable();
         let mut start = CratePositions::new();
-        while !lines.peek().unwrap().starts_with(" 1") {
-            let line = lines.next().unwrap();
-            start.parse_line(line)?;
+        loop {
+            let line = lines
+                .next()
+                .context("expected a line numbering the stacks")?;
+            if line.starts_with(" 1") {
+                break;
+            }
+            start.parse_line(i, line)?;
         }
-        // skip numbers line and blank line after it
-        lines.next();
+        // skip blank line after the numbers
         lines.next();
         // parse instructions
+        let stacks = start.columns.len();
         let mut instructions = vec![];
         for line in lines {
-            let (_, instruction) = Instruction::parse(line)
-                .map_err(|_| format_err!("couldn't parse instruction line"))?;
-            instructions.push(instruction);
+            let inst = parse_line(i, line, Instruction::parse)?;
+            ensure!(
+                (1..=stacks).contains(&inst.from) && (1..=stacks).contains(&inst.to),
+                "`{}` refers to a stack that doesn't exist (there are {})",
+                line.trim(),
+                stacks
+            );
+            instructions.push(inst);
         }
         // Make it so the first popped el of the vecs is the top of the column
         for column in &mut start.columns {
@@ -67,7 +88,17 @@ impl Input {
     }
 }
 
-#[derive(Debug)]
+impl fmt::Display for Input {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "{}", self.start)?;
+        for inst in &self.instructions {
+            writeln!(f, "{}", inst)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
 struct CratePositions {
     columns: Vec<Vec<Crate>>,
 }
@@ -84,63 +115,88 @@ impl CratePositions {
         }
     }
 
-    fn parse_line(&mut self, mut i: &str) -> Result {
-        let mut krate;
-        let mut column = 0;
-        while !i.is_empty() {
-            (i, krate) = Crate::parse(i).map_err(|_| format_err!("couldn't parse [<char>]"))?;
-            self.ensure_len(column + 1);
+    /// Parse a line of the starting positions drawing, which is borrowed from `input`.
+    fn parse_line(&mut self, input: &str, line: &str) -> Result {
+        let crates = parse_line(input, line, many0(Crate::parse))?;
+        self.ensure_len(crates.len());
+        for (column, krate) in crates.into_iter().enumerate() {
             if let Some(krate) = krate {
                 self.columns[column].push(krate);
             }
-            column += 1;
         }
         Ok(())
     }
 
-    fn apply_instruction(&mut self, inst: Instruction) {
+    fn apply_instruction(&mut self, inst: Instruction) -> Result {
         let from = inst.from();
         let to = inst.to();
         for _ in 0..inst.count {
-            let krate = self.columns[from].pop().unwrap();
+            let krate = self.columns[from]
+                .pop()
+                .with_context(|| format!("stack {} ran out of crates", inst.from))?;
             self.columns[to].push(krate);
         }
+        Ok(())
     }
 
-    fn apply_instruction_9001(&mut self, inst: Instruction) {
-        let from = inst.from();
-        let to = inst.to();
-        assert!(from != to);
-        let count = inst.count;
+    fn apply_instruction_9001(&mut self, inst: Instruction) -> Result {
+        let from = &mut self.columns[inst.from()];
+        let start = from.len().checked_sub(inst.count).with_context(|| {
+            format!(
+                "can't move {} crates from stack {}, which only has {}",
+                inst.count,
+                inst.from,
+                from.len()
+            )
+        })?;
+        // moving a stack onto itself puts it back as it was
+        let moved = from.split_off(start);
+        self.columns[inst.to()].extend(moved);
+        Ok(())
+    }
 
-        let from_len = self.columns[from].len();
-        for i in from_len - count..from_len {
-            let val = self.columns[from][i];
-            self.columns[to].push(val);
+    fn top_crates(&self) -> Result<String> {
+        let mut out = String::new();
+        for (idx, column) in self.columns.iter().enumerate() {
+            let top = column
+                .last()
+                .with_context(|| format!("stack {} is empty", idx + 1))?;
+            out.push(top.0);
         }
-        self.columns[from].truncate(from_len - count);
+        Ok(out)
     }
+}
 
-    fn top_crates(&self) -> String {
-        let mut out = String::new();
-        for column in &self.columns {
-            out.push(column[column.len() - 1].0);
+/// Draws the stacks, with their numbers underneath.
+impl fmt::Display for CratePositions {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let height = self.columns.iter().map(Vec::len).max().unwrap_or(0);
+        for row in (0..height).rev() {
+            let cells = self
+                .columns
+                .iter()
+                .map(|column| match column.get(row) {
+                    Some(krate) => format!("[{}]", krate.0),
+                    None => "   ".into(),
+                })
+                .collect::<Vec<_>>();
+            writeln!(f, "{}", cells.join(" "))?;
         }
-        out
+        let numbers = (1..=self.columns.len())
+            .map(|n| format!(" {} ", n))
+            .collect::<Vec<_>>();
+        writeln!(f, "{}", numbers.join(" "))
     }
 }
 
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
 struct Crate(char);
 
 impl Crate {
-    fn parse(i: &str) -> IResult<&str, Option<Self>> {
-        alt((
-            map(Self::parse_crate, |v| Some(v)),
-            value(None, Self::parse_space),
-        ))(i)
+    fn parse(i: &str) -> IResult<'_, Option<Self>> {
+        alt((map(Self::parse_crate, Some), value(None, Self::parse_space)))(i)
     }
-    fn parse_crate(i: &str) -> IResult<&str, Self> {
+    fn parse_crate(i: &str) -> IResult<'_, Self> {
         let (i, _) = tag("[")(i)?;
         let (i, ch) = anychar(i)?;
         let (i, _) = tag("]")(i)?;
@@ -148,7 +204,7 @@ impl Crate {
         Ok((i, Crate(ch)))
     }
 
-    fn parse_space(i: &str) -> IResult<&str, ()> {
+    fn parse_space(i: &str) -> IResult<'_, ()> {
         let (i, _) = tag("   ")(i)?;
         let (i, _) = opt(tag(" "))(i)?;
         Ok((i, ()))
@@ -156,7 +212,7 @@ impl Crate {
 }
 
 /// Remember that we store columns 0-indexed, but they are recorded here as 1-indexed.
-#[derive(Debug)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 struct Instruction {
     count: usize,
     from: usize,
@@ -164,14 +220,14 @@ struct Instruction {
 }
 
 impl Instruction {
-    fn parse(i: &str) -> IResult<&str, Instruction> {
+    fn parse(i: &str) -> IResult<'_, Instruction> {
         let i = i.trim();
         let (i, _) = tag("move ")(i)?;
-        let (i, count): (&str, usize) = map_res(digit1, FromStr::from_str)(i)?;
+        let (i, count) = number(i)?;
         let (i, _) = tag(" from ")(i)?;
-        let (i, from): (&str, usize) = map_res(digit1, FromStr::from_str)(i)?;
+        let (i, from) = number(i)?;
         let (i, _) = tag(" to ")(i)?;
-        let (i, to): (&str, usize) = map_res(digit1, FromStr::from_str)(i)?;
+        let (i, to) = number(i)?;
         Ok((i, Instruction { count, from, to }))
     }
 
@@ -185,3 +241,46 @@ impl Instruction {
         self.to - 1
     }
 }
+
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "move {} from {} to {}", self.count, self.from, self.to)
+    }
+}
+
+#[cfg(test)]
+crate::strategy::roundtrip! {
+    print: ToString::to_string,
+    parse: Input::parse,
+
+    fn strategy() -> impl Strategy<Value = Input> {
+        let krate = (b'A'..=b'Z').prop_map(|ch| Crate(ch.into()));
+        vec(vec(krate, 1..8), 1..10).prop_flat_map(|columns| {
+            let stacks = columns.len();
+            let inst = (0..50usize, 1..=stacks, 1..=stacks)
+                .prop_map(|(count, from, to)| Instruction { count, from, to });
+            let start = CratePositions { columns };
+            vec(inst, 0..20).prop_map(move |instructions| Input {
+                start: start.clone(),
+                instructions,
+            })
+        })
+    }
+}
+
+#[test]
+fn test_display() {
+    let input = Input::parse(EXAMPLE).unwrap();
+    assert_eq!(input.to_string(), EXAMPLE);
+}
+
+const EXAMPLE: &str = "    [D]    
+[N] [C]    
+[Z] [M] [P]
+ 1   2   3 
+
+move 1 from 2 to 1
+move 3 from 1 to 3
+move 2 from 2 to 1
+move 1 from 1 to 2
+";
@@ -1,28 +1,68 @@
+use aoc_core::{Day, Example};
+use aoc_utils::ConstWindows;
 use qu::ick_use::*;
 
-const INPUT: &str = include_str!("../input/6");
+pub struct Solution;
 
-pub fn first() -> Result<u32> {
-    for (idx, window) in INPUT.as_bytes().array_windows::<4>().enumerate() {
-        let mut window = window.clone();
-        window.sort();
-        if window[0] < window[1] && window[1] < window[2] && window[2] < window[3] {
-            return Ok((idx + 4).try_into().unwrap());
+impl Day for Solution {
+    type Input<'a> = &'a [u8];
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    const EXAMPLES: &'static [Example] = &[
+        Example {
+            input: "mjqjpqmgbljsphdztnvjfqwrcgsmlb",
+            part1: Some("7"),
+            part2: Some("19"),
+        },
+        Example {
+            input: "bvwbjplbgvbhsrlpgdmjqwftvncz",
+            part1: Some("5"),
+            part2: Some("23"),
+        },
+        Example {
+            input: "nppdvjthqldpwncqszvftbrmjlhg",
+            part1: Some("6"),
+            part2: Some("23"),
+        },
+        Example {
+            input: "nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg",
+            part1: Some("10"),
+            part2: Some("29"),
+        },
+        Example {
+            input: "zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw",
+            part1: Some("11"),
+            part2: Some("26"),
+        },
+    ];
+
+    fn parse(input: &str) -> Result<&[u8]> {
+        Ok(input.as_bytes())
+    }
+
+    fn part1(input: &&[u8]) -> Result<usize> {
+        for (idx, window) in input.const_windows::<4>().enumerate() {
+            let mut window = *window;
+            window.sort();
+            if window[0] < window[1] && window[1] < window[2] && window[2] < window[3] {
+                return Ok(idx + 4);
+            }
         }
+        bail!("couldn't find 4 non-matching characters")
     }
-    bail!("couldn't find 4 non-matching characters")
-}
 
-pub fn second() -> Result<u32> {
-    'windows: for (idx, window) in INPUT.as_bytes().array_windows::<14>().enumerate() {
-        let mut window = window.clone();
-        window.sort();
-        for i in 0..13 {
-            if window[i] == window[i + 1] {
-                continue 'windows;
+    fn part2(input: &&[u8]) -> Result<usize> {
+        'windows: for (idx, window) in input.const_windows::<14>().enumerate() {
+            let mut window = *window;
+            window.sort();
+            for i in 0..13 {
+                if window[i] == window[i + 1] {
+                    continue 'windows;
+                }
             }
+            return Ok(idx + 14);
         }
-        return Ok((idx + 14).try_into().unwrap());
+        bail!("couldn't find 14 non-matching characters")
     }
-    bail!("couldn't find 14 non-matching characters")
 }
@@ -1,56 +1,76 @@
+use aoc_core::{Day, Example};
+use aoc_utils::parse::{number, parse_line, tag, IResult};
 use nom::{
     branch::alt,
-    bytes::complete::tag,
-    character::complete::digit1,
-    combinator::{all_consuming, map, map_res, rest, value},
+    combinator::{map, rest, value},
     sequence::tuple,
-    IResult,
 };
 use qu::ick_use::*;
+use std::fmt;
 
-const INPUT: &str = include_str!("../input/7");
-
-pub fn first() -> Result<usize> {
-    let fs = FsEntry::from_input(INPUT)?;
-    Ok(fs
-        .walk()
-        .filter_map(|entry| match entry {
-            FsEntry::File { .. } => None,
-            FsEntry::Dir { size, .. } => {
-                if *size <= 100_000 {
-                    Some(*size)
-                } else {
-                    None
+pub struct Solution;
+
+impl Day for Solution {
+    type Input<'a> = FsEntry<'a>;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    const EXAMPLES: &'static [Example] = &[Example {
+        input: EXAMPLE,
+        part1: Some("95437"),
+        part2: Some("24933642"),
+    }];
+
+    fn parse(input: &str) -> Result<FsEntry<'_>> {
+        FsEntry::from_input(input)
+    }
+
+    fn part1(fs: &FsEntry) -> Result<usize> {
+        Ok(fs
+            .walk()
+            .filter_map(|entry| match entry {
+                FsEntry::File { .. } => None,
+                FsEntry::Dir { size, .. } => {
+                    if *size <= 100_000 {
+                        Some(*size)
+                    } else {
+                        None
+                    }
                 }
-            }
-        })
-        .sum())
-}
+            })
+            .sum())
+    }
 
-pub fn second() -> Result<usize> {
-    const TOTAL_SPACE: usize = 70_000_000;
-    const SPACE_NEEDED: usize = 30_000_000;
-    let fs = FsEntry::from_input(INPUT)?;
-    let total_used = fs.size();
-    assert!(TOTAL_SPACE > total_used);
-    let free_available = TOTAL_SPACE - total_used;
-    assert!(SPACE_NEEDED > free_available);
-    let space_to_free = SPACE_NEEDED - free_available;
-
-    Ok(fs
-        .walk()
-        .filter_map(|entry| match entry {
-            FsEntry::File { .. } => None,
-            FsEntry::Dir { size, .. } => {
-                if *size >= space_to_free {
-                    Some(*size)
-                } else {
-                    None
+    fn part2(fs: &FsEntry) -> Result<usize> {
+        const TOTAL_SPACE: usize = 70_000_000;
+        const SPACE_NEEDED: usize = 30_000_000;
+        let total_used = fs.size();
+        ensure!(
+            TOTAL_SPACE > total_used,
+            "files take up {}, more than the disk holds",
+            total_used
+        );
+        let free_available = TOTAL_SPACE - total_used;
+        ensure!(
+            SPACE_NEEDED > free_available,
+            "there's already enough free space"
+        );
+        let space_to_free = SPACE_NEEDED - free_available;
+
+        fs.walk()
+            .filter_map(|entry| match entry {
+                FsEntry::File { .. } => None,
+                FsEntry::Dir { size, .. } => {
+                    if *size >= space_to_free {
+                        Some(*size)
+                    } else {
+                        None
+                    }
                 }
-            }
-        })
-        .min()
-        .unwrap())
+            })
+            .min()
+            .context("no directory is big enough to free the space needed")
+    }
 }
 
 // Filesystem builder
@@ -69,47 +89,68 @@ impl<'a> FsBuilder<'a> {
         }
     }
 
-    fn process(&mut self, line: Line<'a>) {
+    fn process(&mut self, line: Line<'a>) -> Result {
         match line {
-            Line::CmdCdRoot => {
-                assert!(self.dir_stack.is_empty())
-            }
+            Line::CmdCdRoot => self.dir_stack.clear(),
             Line::CmdCdDir { name } => {
+                ensure!(
+                    self.current_dir().child_dir_mut(name).is_some(),
+                    "no directory `{}` has been listed here",
+                    name
+                );
                 self.dir_stack.push(name);
             }
             Line::CmdCdUp => {
                 let dir = self.dir_stack.pop();
-                assert!(dir.is_some());
+                ensure!(dir.is_some(), "`cd ..` from the root directory");
             }
             Line::CmdLs => (),
-            Line::File { name, size } => self.add_file(name, size),
+            Line::File { name, size } => self.add_file(name, size)?,
             Line::Dir { name } => self.add_directory(name),
         }
+        Ok(())
     }
 
-    fn add_directory(&mut self, name: &'a str) {
+    /// The directory we're in, which must exist because we only `cd` into listed directories.
+    fn current_dir(&mut self) -> &mut FsEntry<'a> {
         let mut entry = &mut self.root;
         for dir in &self.dir_stack {
-            entry = entry.child_mut(dir)
+            entry = entry
+                .child_dir_mut(dir)
+                .expect("cannot find current directory");
         }
-        entry.children_mut().push(FsEntry::new_dir(name));
+        entry
+    }
+
+    fn add_directory(&mut self, name: &'a str) {
+        self.current_dir()
+            .children_mut()
+            .push(FsEntry::new_dir(name));
     }
 
-    fn add_file(&mut self, name: &'a str, size: usize) {
+    fn add_file(&mut self, name: &'a str, size: usize) -> Result {
+        // the root holds the largest total, so if that fits they all do
+        ensure!(
+            self.root.size().checked_add(size).is_some(),
+            "total size of files is too large"
+        );
         let mut entry = &mut self.root;
         entry.set_size(entry.size() + size);
         for dir in &self.dir_stack {
-            entry = entry.child_mut(dir);
+            entry = entry
+                .child_dir_mut(dir)
+                .expect("cannot find current directory");
             entry.set_size(entry.size() + size);
         }
         entry.children_mut().push(FsEntry::new_file(name, size));
+        Ok(())
     }
 }
 
 // Would probably be better to use `petgraph` because it has features allowing you to modify a
 // graph you have pointers into.
-#[derive(Debug, Clone)]
-enum FsEntry<'a> {
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FsEntry<'a> {
     Dir {
         name: &'a str,
         children: Vec<FsEntry<'a>>,
@@ -124,9 +165,10 @@ enum FsEntry<'a> {
 impl<'a> FsEntry<'a> {
     fn from_input(input: &'a str) -> Result<Self> {
         let mut fs = FsBuilder::new();
-        for line in input.lines() {
-            let line = Line::parse(line)?;
-            fs.process(line);
+        for (idx, line) in input.lines().enumerate() {
+            let line = parse_line(input, line, Line::parse)?;
+            fs.process(line)
+                .with_context(|| format!("line {}", idx + 1))?;
         }
         Ok(fs.root)
     }
@@ -178,11 +220,10 @@ impl<'a> FsEntry<'a> {
         children
     }
 
-    fn child_mut(&mut self, name: &str) -> &mut FsEntry<'a> {
+    fn child_dir_mut(&mut self, name: &str) -> Option<&mut FsEntry<'a>> {
         self.children_mut()
             .iter_mut()
-            .find(|child| child.name() == name)
-            .expect("cannot find entry with given name")
+            .find(|child| matches!(child, FsEntry::Dir { .. }) && child.name() == name)
     }
 
     fn child_idx(&self, idx: usize) -> Option<&FsEntry<'a>> {
@@ -210,6 +251,31 @@ impl<'a> FsEntry<'a> {
     }
 }
 
+/// A file prints as its line in a listing, and a directory as the commands and output that list
+/// it and everything in it.
+impl fmt::Display for FsEntry<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let FsEntry::Dir { name, children, .. } = self else {
+            return writeln!(f, "{} {}", self.size(), self.name());
+        };
+        writeln!(f, "$ cd {}", name)?;
+        writeln!(f, "$ ls")?;
+        for child in children {
+            match child {
+                FsEntry::Dir { name, .. } => writeln!(f, "dir {}", name)?,
+                FsEntry::File { .. } => write!(f, "{}", child)?,
+            }
+        }
+        for child in children {
+            if let FsEntry::Dir { .. } = child {
+                write!(f, "{}", child)?;
+                writeln!(f, "$ cd ..")?;
+            }
+        }
+        Ok(())
+    }
+}
+
 struct FsIter<'iter, 'a> {
     fs: &'iter FsEntry<'a>,
     index_stack: Vec<usize>,
@@ -269,24 +335,94 @@ enum Line<'a> {
 
 impl<'a> Line<'a> {
     /// Parse a single line.
-    fn parse(i: &'a str) -> Result<Self> {
-        let (_, line) = parse_line(i).map_err(|_| format_err!("failed to parse line"))?;
-        Ok(line)
+    fn parse(i: &'a str) -> IResult<'a, Self> {
+        alt((
+            value(Line::CmdCdRoot, tag("$ cd /")),
+            value(Line::CmdCdUp, tag("$ cd ..")),
+            value(Line::CmdLs, tag("$ ls")),
+            map(tuple((tag("$ cd "), rest)), |(_, name)| Line::CmdCdDir {
+                name,
+            }),
+            map(tuple((tag("dir "), rest)), |(_, name)| Line::Dir { name }),
+            map(tuple((number, tag(" "), rest)), |(size, _, name)| {
+                Line::File { size, name }
+            }),
+        ))(i)
     }
 }
 
-fn parse_line(i: &str) -> IResult<&str, Line> {
-    all_consuming(alt((
-        value(Line::CmdCdRoot, tag("$ cd /")),
-        value(Line::CmdCdUp, tag("$ cd ..")),
-        value(Line::CmdLs, tag("$ ls")),
-        map(tuple((tag("$ cd "), rest)), |(_, name)| Line::CmdCdDir {
-            name,
-        }),
-        map(tuple((tag("dir "), rest)), |(_, name)| Line::Dir { name }),
-        map(
-            tuple((map_res(digit1, str::parse), tag(" "), rest)),
-            |(size, _, name)| Line::File { size, name },
-        ),
-    )))(i)
+#[cfg(test)]
+crate::strategy::roundtrip! {
+    print: |root: &Node| to_entry("/", root).to_string(),
+    parse: |input: &str| FsEntry::from_input(input).map(|root| to_node(&root)),
+
+    /// An owned directory tree for proptest to generate, since `FsEntry` borrows its names.
+    #[derive(Debug, Clone, PartialEq)]
+    enum Node {
+        File(usize),
+        Dir(BTreeMap<String, Node>),
+    }
+
+    fn strategy() -> impl Strategy<Value = Node> {
+        const NAME: &str = "[a-z]{1,8}(\\.[a-z]{1,3})?";
+        let file = (1..1_000_000usize).prop_map(Node::File);
+        let node = file.prop_recursive(3, 40, 6, |inner| {
+            btree_map(NAME, inner, 0..6).prop_map(Node::Dir)
+        });
+        btree_map(NAME, node, 0..6).prop_map(Node::Dir)
+    }
+
+    fn to_entry<'a>(name: &'a str, node: &'a Node) -> FsEntry<'a> {
+        match node {
+            Node::File(size) => FsEntry::new_file(name, *size),
+            Node::Dir(children) => {
+                let children = children
+                    .iter()
+                    .map(|(name, node)| to_entry(name, node))
+                    .collect::<Vec<_>>();
+                FsEntry::Dir {
+                    name,
+                    size: children.iter().map(FsEntry::size).sum(),
+                    children,
+                }
+            }
+        }
+    }
+
+    fn to_node(entry: &FsEntry) -> Node {
+        match entry {
+            FsEntry::File { size, .. } => Node::File(*size),
+            FsEntry::Dir { children, .. } => Node::Dir(
+                children
+                    .iter()
+                    .map(|child| (child.name().to_owned(), to_node(child)))
+                    .collect(),
+            ),
+        }
+    }
 }
+
+const EXAMPLE: &str = "$ cd /
+$ ls
+dir a
+14848514 b.txt
+8504156 c.dat
+dir d
+$ cd a
+$ ls
+dir e
+29116 f
+2557 g
+62596 h.lst
+$ cd e
+$ ls
+584 i
+$ cd ..
+$ cd ..
+$ cd d
+$ ls
+4060174 j
+8033020 d.log
+5626152 d.ext
+7214296 k
+";
@@ -1,121 +1,98 @@
-use itertools::Itertools;
+use aoc_core::{Day, Example};
+use aoc_utils::{Dir4, Grid, Point};
 use qu::ick_use::*;
-use std::fmt;
 
-const INPUT: &str = include_str!("../input/8");
+pub struct Solution;
 
-pub fn first() -> Result<usize> {
-    let input = TreeGrid::parse(INPUT)?;
-    Ok((0..input.width)
-        .cartesian_product(0..input.height())
-        .filter(|(x, y)| input.tree_visible(*x, *y))
-        .count())
-}
-
-pub fn second() -> Result<usize> {
-    let input = TreeGrid::parse(INPUT)?;
-    Ok((0..input.width)
-        .cartesian_product(0..input.height())
-        .map(|(x, y)| input.trees_seen(x, y))
-        .max()
-        .unwrap_or(0))
-}
-
-struct TreeGrid {
-    trees: Vec<u8>,
-    width: usize,
-}
+impl Day for Solution {
+    type Input<'a> = Grid<u8>;
+    type Answer1 = usize;
+    type Answer2 = usize;
 
-impl TreeGrid {
-    fn parse(i: &str) -> Result<Self> {
-        let mut lines = i.lines();
-        let mut trees = vec![];
+    const EXAMPLES: &'static [Example] = &[Example {
+        input: EXAMPLE,
+        part1: Some("21"),
+        part2: Some("8"),
+    }];
 
-        let first = lines.next().context("no input data")?;
-        for ch in first.bytes() {
-            ensure!(ch >= b'0' && ch <= b'9', "tree heights should be digits");
-            trees.push(ch - b'0');
-        }
-        let width = trees.len();
-        for line in lines {
-            let line = line.as_bytes();
-            ensure!(
-                line.len() == width,
-                "found line that didn't match length of first line"
-            );
-            for ch in line.iter().copied() {
-                ensure!(ch >= b'0' && ch <= b'9', "tree heights should be digits");
-                trees.push(ch - b'0');
-            }
-        }
-        assert_eq!(trees.len() % width, 0);
-        Ok(TreeGrid { trees, width })
-    }
-
-    fn tree_height_at(&self, x: usize, y: usize) -> u8 {
-        self.trees[y * self.width + x]
+    fn parse(input: &str) -> Result<Grid<u8>> {
+        Grid::parse(input, |ch| {
+            let height = ch.to_digit(10).context("tree heights should be digits")?;
+            Ok(height as u8)
+        })
     }
 
-    fn height(&self) -> usize {
-        self.trees.len() / self.width
+    fn part1(input: &Grid<u8>) -> Result<usize> {
+        Ok(input.points().filter(|p| tree_visible(input, *p)).count())
     }
 
-    /// Is the tree at (x, y) visible from the edge.
-    fn tree_visible(&self, x: usize, y: usize) -> bool {
-        // Are x and y in bounds.
-        assert!(x < self.width && y * self.width < self.trees.len());
-
-        let height = self.tree_height_at(x, y);
-
-        // x direction
-        (0..x).all(|x_ix| self.tree_height_at(x_ix, y) < height)
-            || (x + 1..self.width).all(|x_ix| self.tree_height_at(x_ix, y) < height)
-            || (0..y).all(|y_ix| self.tree_height_at(x, y_ix) < height)
-            || (y + 1..self.height()).all(|y_ix| self.tree_height_at(x, y_ix) < height)
+    fn part2(input: &Grid<u8>) -> Result<usize> {
+        Ok(input
+            .points()
+            .map(|p| trees_seen(input, p))
+            .max()
+            .unwrap_or(0))
     }
+}
 
-    /// How many trees can the tree at x, y see?
-    fn trees_seen(&self, x: usize, y: usize) -> usize {
-        assert!(x < self.width && y * self.width < self.trees.len());
-
-        let height = self.tree_height_at(x, y);
-        let x_less_taller = (0..x)
-            .rev()
-            .find(|x_ix| self.tree_height_at(*x_ix, y) >= height)
-            .unwrap_or(0);
-        let x_less_count = x - x_less_taller;
-        let x_greater_taller = (x + 1..self.width)
-            .find(|x_ix| self.tree_height_at(*x_ix, y) >= height)
-            .unwrap_or(self.width - 1);
-        let x_greater_count = x_greater_taller - x;
-        let y_less_taller = (0..y)
-            .rev()
-            .find(|y_ix| self.tree_height_at(x, *y_ix) >= height)
-            .unwrap_or(0);
-        let y_less_count = y - y_less_taller;
-        let y_greater_taller = (y + 1..self.height())
-            .find(|y_ix| self.tree_height_at(x, *y_ix) >= height)
-            .unwrap_or(self.height() - 1);
-        let y_greater_count = y_greater_taller - y;
-        x_less_count * x_greater_count * y_less_count * y_greater_count
-    }
+/// Is the tree at `p` visible from the edge.
+fn tree_visible(trees: &Grid<u8>, p: Point) -> bool {
+    let height = trees[p];
+    Dir4::ALL.into_iter().any(|dir| {
+        trees
+            .ray(p, dir.offset())
+            .all(|other| trees[other] < height)
+    })
 }
 
-impl fmt::Debug for TreeGrid {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        for (idx, ch) in self.trees.iter().copied().enumerate() {
-            write!(f, "{}", ch)?;
-            if (idx + 1) % self.width == 0 {
-                writeln!(f)?;
+/// How many trees can the tree at `p` see?
+fn trees_seen(trees: &Grid<u8>, p: Point) -> usize {
+    let height = trees[p];
+    Dir4::ALL
+        .into_iter()
+        .map(|dir| {
+            let mut seen = 0;
+            for other in trees.ray(p, dir.offset()) {
+                seen += 1;
+                if trees[other] >= height {
+                    break;
+                }
             }
-        }
-        Ok(())
-    }
+            seen
+        })
+        .product()
 }
 
 #[test]
 pub fn test_parse() {
-    let input = TreeGrid::parse(INPUT).unwrap();
-    assert_eq!(input.tree_height_at(0, 0), 0);
-    assert_eq!(input.tree_height_at(0, 2), 0);
+    let input = Solution::parse(EXAMPLE).unwrap();
+    assert_eq!(input[Point::new(0, 0)], 3);
+    assert_eq!(input[Point::new(0, 2)], 6);
+    assert_eq!(input[Point::new(4, 3)], 9);
+    assert!(Solution::parse("303\n2a5\n").is_err());
 }
+
+#[cfg(test)]
+crate::strategy::roundtrip! {
+    print: ToString::to_string,
+    parse: Solution::parse,
+
+    fn strategy() -> impl Strategy<Value = Grid<u8>> {
+        (1..10usize, 1..10usize).prop_flat_map(|(width, height)| {
+            vec(0..10u8, width * height).prop_map(move |heights| {
+                let mut grid = Grid::new(width, height, 0);
+                for (p, height) in grid.points().collect::<Vec<_>>().into_iter().zip(heights) {
+                    grid[p] = height;
+                }
+                grid
+            })
+        })
+    }
+}
+
+const EXAMPLE: &str = "30373
+25512
+65332
+33549
+35390
+";
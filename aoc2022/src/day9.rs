@@ -1,60 +1,79 @@
-use nom::{
-    branch::alt,
-    bytes::complete::tag,
-    character::complete::digit1,
-    combinator::{all_consuming, map_res, value},
-    sequence::tuple,
-    IResult,
+use aoc_core::{Day, Example};
+use aoc_utils::{
+    parse::{number, parse_lines, tag, IResult},
+    Dir4, Point,
 };
+use nom::{branch::alt, combinator::value, sequence::tuple};
 use qu::ick_use::*;
 use std::{collections::HashSet, fmt};
-use Dir::*;
-
-const INPUT: &str = include_str!("../input/9");
+use Dir4::*;
+
+pub struct Solution;
+
+impl Day for Solution {
+    type Input<'a> = Vec<Inst>;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    const EXAMPLES: &'static [Example] = &[
+        Example {
+            input: EXAMPLE,
+            part1: Some("13"),
+            part2: Some("1"),
+        },
+        Example {
+            input: EXAMPLE_2,
+            part1: None,
+            part2: Some("36"),
+        },
+    ];
+
+    fn parse(input: &str) -> Result<Vec<Inst>> {
+        Inst::parse(input)
+    }
 
-pub fn first() -> Result<usize> {
-    let input = Inst::parse(INPUT)?;
-    let mut sim = Simulation1::new();
-    for inst in input {
-        sim.step_many(inst.dir, inst.amt);
+    fn part1(input: &Vec<Inst>) -> Result<usize> {
+        let mut sim = Simulation1::new();
+        for inst in input {
+            sim.step_many(inst.dir, inst.amt);
+        }
+        Ok(sim.end_pos_seen.len())
     }
-    Ok(sim.end_pos_seen.len())
-}
 
-pub fn second() -> Result<usize> {
-    let input = Inst::parse(INPUT)?;
-    let mut sim = Simulation2::new();
-    for inst in input {
-        sim.step_many(inst.dir, inst.amt);
+    fn part2(input: &Vec<Inst>) -> Result<usize> {
+        let mut sim = Simulation2::new();
+        for inst in input {
+            sim.step_many(inst.dir, inst.amt);
+        }
+        Ok(sim.end_pos_seen.len())
     }
-    Ok(sim.end_pos_seen.len())
 }
 
 struct Simulation1 {
     iter: usize,
-    start_pos: (isize, isize),
-    end_pos: (isize, isize),
-    end_pos_seen: HashSet<(isize, isize)>,
+    start_pos: Point,
+    end_pos: Point,
+    end_pos_seen: HashSet<Point>,
 }
 
 impl Simulation1 {
     fn new() -> Self {
         Self {
             iter: 0,
-            start_pos: (0, 0),
-            end_pos: (0, 0),
-            end_pos_seen: HashSet::from([(0, 0)]),
+            start_pos: Point::ZERO,
+            end_pos: Point::ZERO,
+            end_pos_seen: HashSet::from([Point::ZERO]),
         }
     }
 
-    fn step(&mut self, dir: Dir) {
-        self.start_pos = dir.move_point(self.start_pos);
+    fn step(&mut self, dir: Dir4) {
+        self.start_pos += dir;
         self.end_pos = step(self.start_pos, self.end_pos);
         self.end_pos_seen.insert(self.end_pos);
         self.iter += 1;
     }
 
-    fn step_many(&mut self, dir: Dir, amt: usize) {
+    fn step_many(&mut self, dir: Dir4, amt: usize) {
         for _ in 0..amt {
             self.step(dir);
         }
@@ -69,13 +88,13 @@ impl Simulation1 {
                 if self.0.end_pos_seen.is_empty() {
                     return Ok(());
                 }
-                let min_x = self.0.end_pos_seen.iter().map(|(x, _)| *x).min().unwrap();
-                let max_x = self.0.end_pos_seen.iter().map(|(x, _)| *x).max().unwrap();
-                let min_y = self.0.end_pos_seen.iter().map(|(_, y)| *y).min().unwrap();
-                let max_y = self.0.end_pos_seen.iter().map(|(_, y)| *y).max().unwrap();
+                let min_x = self.0.end_pos_seen.iter().map(|p| p.x).min().unwrap();
+                let max_x = self.0.end_pos_seen.iter().map(|p| p.x).max().unwrap();
+                let min_y = self.0.end_pos_seen.iter().map(|p| p.y).min().unwrap();
+                let max_y = self.0.end_pos_seen.iter().map(|p| p.y).max().unwrap();
                 for y_idx in min_y..=max_y {
                     for x_idx in min_x..=max_x {
-                        if self.0.end_pos_seen.contains(&(x_idx, y_idx)) {
+                        if self.0.end_pos_seen.contains(&Point::new(x_idx, y_idx)) {
                             write!(f, "#")?;
                         } else {
                             write!(f, ".")?;
@@ -93,21 +112,21 @@ impl Simulation1 {
 
 struct Simulation2 {
     iter: usize,
-    pos: [(isize, isize); 10],
-    end_pos_seen: HashSet<(isize, isize)>,
+    pos: [Point; 10],
+    end_pos_seen: HashSet<Point>,
 }
 
 impl Simulation2 {
     fn new() -> Self {
         Self {
             iter: 0,
-            pos: [(0, 0); 10],
-            end_pos_seen: HashSet::from([(0, 0)]),
+            pos: [Point::ZERO; 10],
+            end_pos_seen: HashSet::from([Point::ZERO]),
         }
     }
 
-    fn step(&mut self, dir: Dir) {
-        self.pos[0] = dir.move_point(self.pos[0]);
+    fn step(&mut self, dir: Dir4) {
+        self.pos[0] += dir;
         for i in 1..10 {
             self.pos[i] = step(self.pos[i - 1], self.pos[i]);
         }
@@ -115,132 +134,113 @@ impl Simulation2 {
         self.iter += 1;
     }
 
-    fn step_many(&mut self, dir: Dir, amt: usize) {
+    fn step_many(&mut self, dir: Dir4, amt: usize) {
         for _ in 0..amt {
             self.step(dir);
         }
     }
 }
 
-#[derive(Copy, Clone, Debug)]
-enum Dir {
-    Right,
-    Up,
-    Left,
-    Down,
-}
-
-impl Dir {
-    fn move_point(self, p: (isize, isize)) -> (isize, isize) {
-        match self {
-            Left => (p.0 - 1, p.1),
-            Right => (p.0 + 1, p.1),
-            Up => (p.0, p.1 - 1),
-            Down => (p.0, p.1 + 1),
-        }
-    }
-}
-
-#[derive(Debug)]
-struct Inst {
-    dir: Dir,
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Inst {
+    dir: Dir4,
     amt: usize,
 }
 
 impl Inst {
     fn parse(i: &str) -> Result<Vec<Self>> {
-        i.lines().map(Self::parse_line).collect()
+        Ok(parse_lines(i, parse_line)?)
     }
+}
 
-    fn parse_line(i: &str) -> Result<Inst> {
-        let (_, inst) = parse_line(i).map_err(|_| format_err!("couldn't parse input"))?;
-        Ok(inst)
+impl fmt::Display for Inst {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let dir = match self.dir {
+            Left => 'L',
+            Right => 'R',
+            Up => 'U',
+            Down => 'D',
+        };
+        write!(f, "{} {}", dir, self.amt)
     }
 }
 
-fn parse_line(i: &str) -> IResult<&str, Inst> {
+fn parse_line(i: &str) -> IResult<'_, Inst> {
     let dir_parser = alt((
         value(Left, tag("L")),
         value(Right, tag("R")),
         value(Up, tag("U")),
         value(Down, tag("D")),
     ));
-    let val_parser = map_res(digit1, <usize as std::str::FromStr>::from_str);
-    let (i, (dir, _, amt)) = all_consuming(tuple((dir_parser, tag(" "), val_parser)))(i)?;
+    let (i, (dir, _, amt)) = tuple((dir_parser, tag(" "), number))(i)?;
     Ok((i, Inst { dir, amt }))
 }
 
 /// Assume that the start pos has moved this step. Calculate the end move.
 ///
-/// Rules for each direciton:
-///  - If it's the same it doesn't move
-///  - If it's 1 away it moves only if the other direction is 2+ away
-///  - If it's 2 away it always moves
-fn step(leader: (isize, isize), us: (isize, isize)) -> (isize, isize) {
-    let leader_x_diff = leader.0 - us.0;
-    let leader_y_diff = leader.1 - us.1;
-
-    let us_x_diff = match leader_x_diff {
-        isize::MIN..=-2 => -1,
-        -1 => {
-            if leader_y_diff.abs() >= 2 {
-                -1
-            } else {
-                0
-            }
-        }
-        0 => 0,
-        1 => {
-            if leader_y_diff.abs() >= 2 {
-                1
-            } else {
-                0
-            }
-        }
-        2..=isize::MAX => 1,
-        _ => unreachable!(),
-    };
-    let us_y_diff = match leader_y_diff {
-        isize::MIN..=-2 => -1,
-        -1 => {
-            if leader_x_diff.abs() >= 2 {
-                -1
-            } else {
-                0
-            }
-        }
-        0 => 0,
-        1 => {
-            if leader_x_diff.abs() >= 2 {
-                1
-            } else {
-                0
-            }
-        }
-        2..=isize::MAX => 1,
-        _ => unreachable!(),
-    };
-    (us.0 + us_x_diff, us.1 + us_y_diff)
+/// The end only moves once it's 2+ away from the leader in either direction, and then it steps one
+/// place towards the leader in each direction it isn't level in (so diagonally if need be).
+fn step(leader: Point, us: Point) -> Point {
+    let diff = leader - us;
+    if diff.chebyshev() >= 2 {
+        us + diff.signum()
+    } else {
+        us
+    }
 }
 
 #[test]
 fn test_move_point() {
-    assert_eq!(Up.move_point((0, 0)), ((0, -1)));
-    assert_eq!(Up.move_point((1, 2)), ((1, 1)));
-    assert_eq!(Left.move_point((0, 0)), ((-1, 0)));
-    assert_eq!(Right.move_point((1, 2)), ((2, 2)));
+    let p = Point::new;
+    assert_eq!(p(0, 0) + Up, p(0, -1));
+    assert_eq!(p(1, 2) + Up, p(1, 1));
+    assert_eq!(p(0, 0) + Left, p(-1, 0));
+    assert_eq!(p(1, 2) + Right, p(2, 2));
 }
 
 #[test]
 fn test_step() {
-    assert_eq!(step((0, 0), (0, 0)), (0, 0));
-    assert_eq!(step((1, 0), (0, 0)), (0, 0));
-    assert_eq!(step((1, 1), (0, 0)), (0, 0));
-    assert_eq!(step((2, 0), (0, 0)), (1, 0));
-    assert_eq!(step((2, 1), (0, 0)), (1, 1));
-    assert_eq!(step((2, 2), (0, 0)), (1, 1));
-    assert_eq!(step((3, 2), (1, 1)), (2, 2));
-    assert_eq!(step((-2, -1), (0, 0)), (-1, -1));
-    assert_eq!(step((0, 0), (-2, -1)), (-1, 0));
-    assert_eq!(step((62, 66), (62, 64)), (62, 65));
+    let p = Point::new;
+    assert_eq!(step(p(0, 0), p(0, 0)), p(0, 0));
+    assert_eq!(step(p(1, 0), p(0, 0)), p(0, 0));
+    assert_eq!(step(p(1, 1), p(0, 0)), p(0, 0));
+    assert_eq!(step(p(2, 0), p(0, 0)), p(1, 0));
+    assert_eq!(step(p(2, 1), p(0, 0)), p(1, 1));
+    assert_eq!(step(p(2, 2), p(0, 0)), p(1, 1));
+    assert_eq!(step(p(3, 2), p(1, 1)), p(2, 2));
+    assert_eq!(step(p(-2, -1), p(0, 0)), p(-1, -1));
+    assert_eq!(step(p(0, 0), p(-2, -1)), p(-1, 0));
+    assert_eq!(step(p(62, 66), p(62, 64)), p(62, 65));
+}
+
+const EXAMPLE: &str = "R 4
+U 4
+L 3
+D 1
+R 4
+D 1
+L 5
+R 2
+";
+
+const EXAMPLE_2: &str = "R 5
+U 8
+L 8
+D 3
+R 17
+D 10
+L 25
+U 20
+";
+
+#[cfg(test)]
+crate::strategy::roundtrip! {
+    print: lines,
+    parse: Inst::parse,
+
+    fn strategy() -> impl Strategy<Value = Vec<Inst>> {
+        let inst =
+            (select(Dir4::ALL.to_vec()), 0..100usize).prop_map(|(dir, amt)| Inst { dir, amt });
+        vec(inst, 1..50)
+    }
 }
@@ -0,0 +1,29 @@
+#[cfg(test)]
+mod strategy;
+
+mod day1;
+mod day10;
+mod day11;
+mod day2;
+mod day3;
+mod day4;
+mod day5;
+mod day6;
+mod day7;
+mod day8;
+mod day9;
+
+aoc_core::days! {
+    2022;
+    1 => day1,
+    2 => day2,
+    3 => day3,
+    4 => day4,
+    5 => day5,
+    6 => day6,
+    7 => day7,
+    8 => day8,
+    9 => day9,
+    10 => day10,
+    11 => day11,
+}
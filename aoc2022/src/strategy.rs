@@ -0,0 +1,73 @@
+//! proptest strategies shared by the days' parser tests.
+use proptest::{prelude::*, sample::select};
+
+/// Characters to splice into inputs: the ones our formats are built from, plus some that never
+/// appear in them.
+const NOISE: &[char] = &[
+    '0', '1', '9', '-', ' ', '\n', ',', ':', '[', ']', '$', '/', '.', 'a', 'z', 'A', 'Z', '\t',
+    'é', '\0',
+];
+
+/// `valid` with one random edit: a few characters deleted, inserted or replaced, or everything
+/// after some point cut off.
+pub fn mutated(valid: String) -> impl Strategy<Value = String> {
+    let len = valid.chars().count();
+    (0..=len, 1..4usize, select(NOISE), 0..4u8).prop_map(move |(at, n, ch, edit)| {
+        let mut chars = valid.chars().collect::<Vec<_>>();
+        let end = (at + n).min(chars.len());
+        match edit {
+            0 => drop(chars.drain(at..end)),
+            1 => chars.insert(at, ch),
+            2 => drop(chars.splice(at..end, [ch])),
+            _ => chars.truncate(at),
+        }
+        chars.into_iter().collect()
+    })
+}
+
+/// Print each item on its own line, as most inputs are laid out.
+pub fn lines<T: ToString>(items: &[T]) -> String {
+    items.iter().map(|item| item.to_string() + "\n").collect()
+}
+
+/// Property tests for a day's parser: `parse` reads back whatever `print` writes for each value
+/// from `strategy()`, and doesn't panic on mangled versions of it.
+///
+/// The items, which must include `fn strategy()`, go in a `roundtrip` module that can see the
+/// day's own items and the usual proptest ones.
+macro_rules! roundtrip {
+    (print: $print:expr, parse: $parse:expr, $($item:item)*) => {
+        mod roundtrip {
+            #[allow(unused_imports)]
+            use {
+                super::*,
+                $crate::strategy::{lines, mutated},
+                std::collections::BTreeMap,
+                proptest::{
+                    collection::{btree_map, vec},
+                    prelude::*,
+                    sample::{select, subsequence},
+                },
+            };
+
+            $($item)*
+
+            proptest! {
+                #[test]
+                fn test_roundtrip(value in strategy()) {
+                    let text = ($print)(&value);
+                    prop_assert_eq!(($parse)(&text).unwrap(), value);
+                }
+
+                #[test]
+                fn test_malformed(
+                    text in strategy().prop_flat_map(|value| mutated(($print)(&value)))
+                ) {
+                    let _ = ($parse)(&text);
+                }
+            }
+        }
+    };
+}
+
+pub(crate) use roundtrip;